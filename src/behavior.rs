@@ -1,51 +1,67 @@
+use bevy::math::Vec2;
+use bevy_turborand::TurboRand;
 use hexx::{EdgeDirection, Hex};
-use pathfinding::directed::dijkstra::dijkstra;
-use std::fmt::Debug;
+use pathfinding::directed::{bfs::bfs, dijkstra::dijkstra};
+use std::{fmt::Debug, ops::RangeInclusive};
 use unique_type_id::{TypeId, UniqueTypeId as _};
 
-use crate::grid::{
-    cell::{Air, BoardSlice},
-    BoardState,
+use crate::{
+    grid::{
+        cell::{Agent, Air, BoardSlice, CellData, Emitter, Phase, Properties},
+        BoardState,
+    },
+    rng::CellRng,
 };
 
-pub type StateId = TypeId<u8>;
-
-type States<const C: usize> = [StateId; C];
+mod query;
+pub use query::{Directions, Frame, Heading, Offset, StateQuery, StateSet};
 
-#[derive(Debug)]
-pub enum StateQuery<const S: usize> {
-    Any(States<S>),
-    Except(States<S>),
-}
+pub type StateId = TypeId<u8>;
 
-impl<const S: usize> StateQuery<S> {
-    pub fn get(&self, index: usize) -> Option<StateId> {
-        match self {
-            StateQuery::Any(states) | StateQuery::Except(states) => states.get(index).copied(),
-        }
+/// A mutation of the board caused by a single cell.
+pub trait Step {
+    /// Try to generate a [`BoardSlice`] or return `None` if not
+    /// applicable.
+    fn apply(self, _hex: Hex, _states: &BoardState, _rng: &mut CellRng) -> Option<BoardSlice>;
+
+    /// Put this step behind a pointer so it can be stored alongside
+    /// steps of other types.
+    fn boxed<'a>(self) -> BoxedStep<'a>
+    where
+        Self: Sized + 'a,
+    {
+        Box::new(self)
     }
+}
 
-    pub fn iter(&self) -> impl Iterator<Item = StateId> {
-        match self {
-            StateQuery::Any(states) | StateQuery::Except(states) => (*states).into_iter(),
-        }
-    }
+/// An object safe version of [`Step`], for behaviors that are put
+/// together at runtime.
+pub trait DynStep {
+    fn apply_boxed(
+        self: Box<Self>,
+        hex: Hex,
+        states: &BoardState,
+        rng: &mut CellRng,
+    ) -> Option<BoardSlice>;
+}
 
-    pub fn len(&self) -> usize {
-        match self {
-            StateQuery::Any(states) => states.len(),
-            StateQuery::Except(states) => states.len(),
-        }
+impl<S: Step> DynStep for S {
+    fn apply_boxed(
+        self: Box<Self>,
+        hex: Hex,
+        states: &BoardState,
+        rng: &mut CellRng,
+    ) -> Option<BoardSlice> {
+        (*self).apply(hex, states, rng)
     }
 }
 
-type Directions<const C: usize> = [EdgeDirection; C];
+pub type BoxedStep<'a> = Box<dyn DynStep + 'a>;
 
-/// A mutation of the board caused by a single cell.
-pub trait Step {
-    /// Try to generate a [`BoardSlice`] or return `None` if not
-    /// applicable.
-    fn apply(self, _hex: Hex, _states: &BoardState, _rng: f32) -> Option<BoardSlice>;
+impl Step for BoxedStep<'_> {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        self.apply_boxed(hex, states, rng)
+    }
 }
 
 /// Try first [`Step`] in tuple and if it fails, try second, and so
@@ -53,7 +69,7 @@ pub trait Step {
 macro_rules! impl_step_or_tuple {
     ($first: tt, $($rest: tt),+) => {
         impl<$first: Step, $($rest: Step),*> Step for ($first, $($rest),*) {
-            fn apply(self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
+            fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
                 #[allow(non_snake_case)]
                 let ($first, $($rest,)*) = self;
                 $first.apply(hex, states, rng)
@@ -75,6 +91,108 @@ impl_step_or_tuple!(A, B, C, D, E, F, G, H);
 impl_step_or_tuple!(A, B, C, D, E, F, G, H, I);
 impl_step_or_tuple!(A, B, C, D, E, F, G, H, I, J);
 impl_step_or_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_step_or_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+impl_step_or_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M);
+impl_step_or_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N);
+impl_step_or_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O);
+impl_step_or_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
+
+/// Apply every [`Step`] and merge their [`BoardSlice`]'s so a cell
+/// can do more than one thing in a tick.
+///
+/// Fails when any of the steps fail, or when two of them write to
+/// the same hex, without applying any of them.
+#[derive(Debug)]
+pub struct All<T>(pub T);
+
+macro_rules! impl_step_all_tuple {
+    ($($step: tt),+) => {
+        impl<$($step: Step),+> Step for All<($($step,)+)> {
+            fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+                #[allow(non_snake_case)]
+                let ($($step,)+) = self.0;
+                let slice = BoardSlice::EMPTY;
+                $(
+                    let slice = slice.merge($step.apply(hex, states, rng)?)?;
+                )+
+                Some(slice)
+            }
+        }
+    };
+}
+
+impl_step_all_tuple!(A);
+impl_step_all_tuple!(A, B);
+impl_step_all_tuple!(A, B, C);
+impl_step_all_tuple!(A, B, C, D);
+impl_step_all_tuple!(A, B, C, D, E);
+impl_step_all_tuple!(A, B, C, D, E, F);
+impl_step_all_tuple!(A, B, C, D, E, F, G);
+impl_step_all_tuple!(A, B, C, D, E, F, G, H);
+impl_step_all_tuple!(A, B, C, D, E, F, G, H, I);
+impl_step_all_tuple!(A, B, C, D, E, F, G, H, I, J);
+impl_step_all_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+impl_step_all_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
+impl_step_all_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M);
+impl_step_all_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N);
+impl_step_all_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O);
+impl_step_all_tuple!(A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P);
+
+impl<S: Step> Step for All<Vec<S>> {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        self.0
+            .into_iter()
+            .try_fold(BoardSlice::EMPTY, |slice, step| {
+                slice.merge(step.apply(hex, states, rng)?)
+            })
+    }
+}
+
+/// Like [`All`], except a later step overwrites whatever an earlier
+/// one wrote to the same hex, along with its velocity and data, so a
+/// cell can move and leave something behind in the hex it moved out
+/// of.
+///
+/// Every step sees the board as it was before any of them, so two
+/// steps that move cells can overwrite a cell one of them displaced.
+/// Fails without applying anything when any of the steps fail.
+#[derive(Debug)]
+pub struct Overwrite<T>(pub T);
+
+macro_rules! impl_step_overwrite_tuple {
+    ($($step: tt),+) => {
+        impl<$($step: Step),+> Step for Overwrite<($($step,)+)> {
+            fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+                #[allow(non_snake_case)]
+                let ($($step,)+) = self.0;
+                let slice = BoardSlice::EMPTY;
+                $(
+                    let slice = slice.overlay($step.apply(hex, states, rng)?);
+                )+
+                Some(slice)
+            }
+        }
+    };
+}
+
+impl_step_overwrite_tuple!(A);
+impl_step_overwrite_tuple!(A, B);
+impl_step_overwrite_tuple!(A, B, C);
+impl_step_overwrite_tuple!(A, B, C, D);
+impl_step_overwrite_tuple!(A, B, C, D, E);
+impl_step_overwrite_tuple!(A, B, C, D, E, F);
+impl_step_overwrite_tuple!(A, B, C, D, E, F, G);
+impl_step_overwrite_tuple!(A, B, C, D, E, F, G, H);
+
+impl<S: Step> Step for Overwrite<Vec<S>> {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        self.0
+            .into_iter()
+            .try_fold(BoardSlice::EMPTY, |slice, step| {
+                Some(slice.overlay(step.apply(hex, states, rng)?))
+            })
+    }
+}
 
 /// Do nothing.
 ///
@@ -84,15 +202,15 @@ impl_step_or_tuple!(A, B, C, D, E, F, G, H, I, J, K);
 pub struct Noop;
 
 impl Step for Noop {
-    fn apply(self, _hex: Hex, _states: &BoardState, _rng: f32) -> Option<BoardSlice> {
+    fn apply(self, _hex: Hex, _states: &BoardState, _rng: &mut CellRng) -> Option<BoardSlice> {
         None
     }
 }
 
-pub struct QueryTest<const S: usize>(pub StateQuery<S>);
+pub struct QueryTest(pub StateQuery);
 
-impl<const S: usize> Step for QueryTest<S> {
-    fn apply(self, _hex: Hex, _states: &BoardState, _rng: f32) -> Option<BoardSlice> {
+impl Step for QueryTest {
+    fn apply(self, _hex: Hex, _states: &BoardState, _rng: &mut CellRng) -> Option<BoardSlice> {
         None
     }
 }
@@ -101,24 +219,24 @@ impl<const S: usize> Step for QueryTest<S> {
 ///
 /// If this cell on touching the edge of a screen in any of the
 /// specified direction, then it turns to an [`Air`] state.
-pub struct Offscreen<const D: usize>(pub Directions<D>);
+pub struct Offscreen(pub Directions);
 
-impl<const D: usize> Step for Offscreen<D> {
-    fn apply(self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
+impl Step for Offscreen {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
         if self
             .0
-            .into_iter()
+            .edges(states.frame(hex))
             .map(|direction| hex.neighbor(direction))
             .any(|hex| states.get_current(hex).is_none())
         {
-            Set([Air::id()]).apply(hex, states, rng)
+            Set(Air::id().into()).apply(hex, states, rng)
         } else {
             None
         }
     }
 }
 
-impl<const D: usize> Debug for Offscreen<D> {
+impl Debug for Offscreen {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Offscreen({:?})", self.0)
     }
@@ -126,68 +244,91 @@ impl<const D: usize> Debug for Offscreen<D> {
 
 /// Convert other nearby cells into another state on collision.
 #[derive(Debug)]
-pub struct Infect<const D: usize, const O: usize, const I: usize> {
-    pub directions: Directions<D>,
-    pub open: StateQuery<O>,
-    pub into: States<I>,
+pub struct Infect {
+    pub directions: Directions,
+    pub open: StateQuery,
+    pub into: StateSet,
 }
 
-impl<const D: usize, const O: usize, const I: usize> Step for Infect<D, O, I> {
-    fn apply(self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
-        let i = (rng * self.directions.len() as f32) as usize;
-        let to = hex.neighbor(self.directions[i]);
+impl Step for Infect {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let to = hex.neighbor(self.directions.choose(states.frame(hex), rng)?);
         if states.is_state(to, &self.open) {
-            let i = (rng * self.into.len() as f32) as usize;
-            self.into.get(i).map(|id| BoardSlice(vec![(to, *id)]))
+            self.into
+                .choose(rng)
+                .map(|id| BoardSlice::new(vec![(to, id)]))
         } else {
             None
         }
     }
 }
 
+/// Like [`Infect`], except the chance of converting a neighbor comes
+/// from its [`Properties::acid_resistance`], and this cell turns into
+/// one of `spent` when it does.
+#[derive(Debug)]
+pub struct Dissolve {
+    pub directions: Directions,
+    /// The chance of dissolving a neighbor with no resistance.
+    pub strength: f32,
+    pub into: StateSet,
+    pub spent: StateSet,
+}
+
+impl Step for Dissolve {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let to = hex.neighbor(self.directions.choose(states.frame(hex), rng)?);
+        let resistance = states.properties(states.get_next(to)?).acid_resistance;
+        if resistance >= 1.0 || rng.f32() >= self.strength * (1.0 - resistance) {
+            return None;
+        }
+        Some(BoardSlice::new(vec![
+            (to, self.into.choose(rng)?),
+            (hex, self.spent.choose(rng)?),
+        ]))
+    }
+}
+
 /// Like [`Infect`], except both cells turn into the same state.
 #[derive(Debug)]
-pub struct Annihilate<const D: usize, const O: usize, const I: usize> {
-    pub directions: Directions<D>,
-    pub open: StateQuery<O>,
-    pub into: States<I>,
+pub struct Annihilate {
+    pub directions: Directions,
+    pub open: StateQuery,
+    pub into: StateSet,
 }
 
-impl<const D: usize, const O: usize, const I: usize> Step for Annihilate<D, O, I> {
-    fn apply(self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
-        let i = (rng * self.directions.len() as f32) as usize;
-        let to = hex.neighbor(self.directions[i]);
+impl Step for Annihilate {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let to = hex.neighbor(self.directions.choose(states.frame(hex), rng)?);
         if states.is_state(to, &self.open) {
-            let i = (rng * self.into.len() as f32) as usize;
             self.into
-                .get(i)
-                .map(|id| BoardSlice(vec![(hex, *id), (to, *id)]))
+                .choose(rng)
+                .map(|id| BoardSlice::new(vec![(hex, id), (to, id)]))
         } else {
             None
         }
     }
 }
 
-/// Drag another cell.
+/// Move into an `open` neighbor, and pull the cell behind into the
+/// hex this one left when it is one of `drag`.
 #[derive(Debug)]
-pub struct Drag<const DIR: usize, const O: usize, const D: usize> {
-    pub directions: Directions<DIR>,
-    pub open: StateQuery<O>,
-    pub drag: StateQuery<D>,
+pub struct Drag {
+    pub directions: Directions,
+    pub open: StateQuery,
+    pub drag: StateQuery,
 }
 
-impl<const DIR: usize, const O: usize, const D: usize> Step for Drag<DIR, O, D> {
-    fn apply(self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
-        let i = (rng * self.directions.len() as f32) as usize;
-        let direction = self.directions[i];
-        let swap = RandomSwap::adjacent(self.directions, self.open);
-        let ((from, from_id), (to, to_id)) = swap.in_direction(hex, direction, 1, states)?;
-        let dir = to.main_direction_to(from);
-        let drag = from.neighbor(dir);
-        let drag_id = *states.get_current(drag)?;
+impl Step for Drag {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let to = hex.neighbor(self.directions.choose(states.frame(hex), rng)?);
+        let from_id = states.get_next(hex)?;
+        let to_id = states.find_state(to, &self.open)?;
+        let drag = hex.neighbor(to.main_direction_to(hex));
+        let drag_id = states.get_next(drag)?;
         if states.is_state(drag, &self.drag) {
-            Some(BoardSlice(vec![
-                (from, drag_id),
+            Some(BoardSlice::new(vec![
+                (hex, drag_id),
                 (to, from_id),
                 (drag, to_id),
             ]))
@@ -205,16 +346,9 @@ pub struct Chance<S: Step> {
 }
 
 impl<S: Step> Step for Chance<S> {
-    fn apply(self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
-        if rng < self.chance {
-            self.to.apply(
-                hex,
-                states,
-                // Since we only tick the containing Step when less
-                // than chance, we remap the `rng` value back over the
-                // chance value to be back between 0.0 - 1.0
-                rng / self.chance,
-            )
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        if rng.f32() < self.chance {
+            self.to.apply(hex, states, rng)
         } else {
             None
         }
@@ -231,7 +365,7 @@ pub struct Choose<A: Step, B: Step> {
 }
 
 impl<A: Step, B: Step> Step for Choose<A, B> {
-    fn apply(self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
         (
             Chance {
                 to: self.a,
@@ -250,6 +384,73 @@ impl<A: Step, B: Step> Choose<A, B> {
     }
 }
 
+/// Randomly choose between any number of [`Step`]'s, each as likely
+/// as its weight compared to the total.
+///
+/// Use [`BoxedStep`] to mix steps of different types, or to build
+/// the list at runtime. There is no rule file format to load one from
+/// yet; that would need its own loader on top of [`BoxedStep`].
+pub struct Weighted<S: Step> {
+    pub steps: Vec<(f32, S)>,
+    /// When the chosen step fails, try the others in order.
+    pub fall_through: bool,
+}
+
+impl<S: Step> Weighted<S> {
+    /// Only ever try the chosen step.
+    pub fn new(steps: impl IntoIterator<Item = (f32, S)>) -> Self {
+        Self {
+            steps: steps.into_iter().collect(),
+            fall_through: false,
+        }
+    }
+
+    /// Try the chosen step first, then the others in order until one
+    /// succeeds.
+    pub fn fall_through(steps: impl IntoIterator<Item = (f32, S)>) -> Self {
+        Self {
+            steps: steps.into_iter().collect(),
+            fall_through: true,
+        }
+    }
+}
+
+impl<S: Step> Step for Weighted<S> {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let mut steps = self
+            .steps
+            .into_iter()
+            .filter(|(weight, _step)| *weight > 0.0)
+            .collect::<Vec<_>>();
+        let total = steps.iter().map(|(weight, _step)| weight).sum::<f32>();
+        let mut target = rng.f32() * total;
+        let mut chosen = steps.len().checked_sub(1)?;
+        for (i, (weight, _step)) in steps.iter().enumerate() {
+            if target < *weight {
+                chosen = i;
+                break;
+            }
+            target -= weight;
+        }
+        let (_weight, step) = steps.remove(chosen);
+        step.apply(hex, states, rng).or_else(|| {
+            if self.fall_through {
+                steps
+                    .into_iter()
+                    .find_map(|(_weight, step)| step.apply(hex, states, rng))
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl<S: Step + Debug> Debug for Weighted<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Weighted({:?})", self.steps)
+    }
+}
+
 /// Assert a step is applied.
 ///
 /// If the step fails to apply, an empty BoardSlice is returned
@@ -260,7 +461,7 @@ impl<A: Step, B: Step> Choose<A, B> {
 pub struct Assert<S: Step>(pub S);
 
 impl<S: Step> Step for Assert<S> {
-    fn apply(self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
         self.0.apply(hex, states, rng).or(Some(BoardSlice::EMPTY))
     }
 }
@@ -281,7 +482,7 @@ impl<S: Step> Step for Assert<S> {
 ///
 /// ```
 /// // Assert there is an Air state to the top left of the current position.
-/// AssertFn(|| states.is_state(hex.neighbor(EdgeDirection::POINTY_TOP_LEFT), &[Air::id()]))
+/// AssertFn(|| states.is_state(hex.neighbor(EdgeDirection::POINTY_TOP_LEFT), &StateQuery::any([Air::id()])))
 /// ```
 pub struct AssertFn<C: FnOnce() -> bool>(
     /// The condition to assert.
@@ -289,7 +490,7 @@ pub struct AssertFn<C: FnOnce() -> bool>(
 );
 
 impl<C: FnOnce() -> bool> Step for AssertFn<C> {
-    fn apply(self, _hex: Hex, _states: &BoardState, _rng: f32) -> Option<BoardSlice> {
+    fn apply(self, _hex: Hex, _states: &BoardState, _rng: &mut CellRng) -> Option<BoardSlice> {
         if self.0() {
             None
         } else {
@@ -312,7 +513,7 @@ impl<C: FnOnce() -> bool> Debug for AssertFn<C> {
 pub struct Stop;
 
 impl Step for Stop {
-    fn apply(self, _hex: Hex, _states: &BoardState, _rng: f32) -> Option<BoardSlice> {
+    fn apply(self, _hex: Hex, _states: &BoardState, _rng: &mut CellRng) -> Option<BoardSlice> {
         Some(BoardSlice::EMPTY)
     }
 }
@@ -323,7 +524,7 @@ impl Step for Stop {
 pub struct Output<'a, T>(pub &'a str, pub T);
 
 impl<'a, T: Step + Debug> Step for Output<'a, T> {
-    fn apply(self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
         println!("{}: {:?}", self.0, self.1);
         self.1.apply(hex, states, rng)
     }
@@ -334,23 +535,23 @@ impl<'a, T: Step + Debug> Step for Output<'a, T> {
 pub struct Message<'a>(pub &'a str);
 
 impl<'a> Step for Message<'a> {
-    fn apply(self, _hex: Hex, _states: &BoardState, _rng: f32) -> Option<BoardSlice> {
+    fn apply(self, _hex: Hex, _states: &BoardState, _rng: &mut CellRng) -> Option<BoardSlice> {
         println!("{}", self.0);
         None
     }
 }
 
 #[derive(Debug)]
-pub struct MaybeNear<const S: usize, O: Step, X: Step> {
-    states: StateQuery<S>,
+pub struct MaybeNear<O: Step, X: Step> {
+    states: StateQuery,
     range: u32,
     count: usize,
     then: O,
     otherwise: X,
 }
 
-impl<const S: usize, O: Step, X: Step> Step for MaybeNear<S, O, X> {
-    fn apply(self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
+impl<O: Step, X: Step> Step for MaybeNear<O, X> {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
         let count = hex
             .xrange(self.range)
             .filter(|hex| states.is_state(*hex, &self.states))
@@ -363,8 +564,8 @@ impl<const S: usize, O: Step, X: Step> Step for MaybeNear<S, O, X> {
     }
 }
 
-impl<const S: usize, O: Step, X: Step> MaybeNear<S, O, X> {
-    pub fn new(states: StateQuery<S>, range: u32, count: usize, then: O, otherwise: X) -> Self {
+impl<O: Step, X: Step> MaybeNear<O, X> {
+    pub fn new(states: StateQuery, range: u32, count: usize, then: O, otherwise: X) -> Self {
         Self {
             states,
             range,
@@ -374,7 +575,7 @@ impl<const S: usize, O: Step, X: Step> MaybeNear<S, O, X> {
         }
     }
 
-    pub fn any_adjacent(states: StateQuery<S>, then: O, otherwise: X) -> Self {
+    pub fn any_adjacent(states: StateQuery, then: O, otherwise: X) -> Self {
         Self {
             states,
             range: 1,
@@ -384,7 +585,7 @@ impl<const S: usize, O: Step, X: Step> MaybeNear<S, O, X> {
         }
     }
 
-    pub fn any(states: StateQuery<S>, range: u32, then: O, otherwise: X) -> Self {
+    pub fn any(states: StateQuery, range: u32, then: O, otherwise: X) -> Self {
         Self {
             states,
             range,
@@ -394,7 +595,7 @@ impl<const S: usize, O: Step, X: Step> MaybeNear<S, O, X> {
         }
     }
 
-    pub fn some_adjacent(states: StateQuery<S>, count: usize, then: O, otherwise: X) -> Self {
+    pub fn some_adjacent(states: StateQuery, count: usize, then: O, otherwise: X) -> Self {
         Self {
             states,
             range: 1,
@@ -411,35 +612,24 @@ pub struct Near;
 
 impl Near {
     #[allow(clippy::new_ret_no_self)]
-    pub fn new<const S: usize, O: Step>(
-        states: StateQuery<S>,
+    pub fn new<O: Step>(
+        states: StateQuery,
         range: u32,
         count: usize,
         then: O,
-    ) -> MaybeNear<S, O, Noop> {
+    ) -> MaybeNear<O, Noop> {
         MaybeNear::new(states, range, count, then, Noop)
     }
 
-    pub fn any_adjacent<const S: usize, O: Step>(
-        states: StateQuery<S>,
-        then: O,
-    ) -> MaybeNear<S, O, Noop> {
+    pub fn any_adjacent<O: Step>(states: StateQuery, then: O) -> MaybeNear<O, Noop> {
         MaybeNear::any_adjacent(states, then, Noop)
     }
 
-    pub fn any<const S: usize, O: Step>(
-        states: StateQuery<S>,
-        range: u32,
-        then: O,
-    ) -> MaybeNear<S, O, Noop> {
+    pub fn any<O: Step>(states: StateQuery, range: u32, then: O) -> MaybeNear<O, Noop> {
         MaybeNear::any(states, range, then, Noop)
     }
 
-    pub fn some_adjacent<const S: usize, O: Step>(
-        states: StateQuery<S>,
-        count: usize,
-        then: O,
-    ) -> MaybeNear<S, O, Noop> {
+    pub fn some_adjacent<O: Step>(states: StateQuery, count: usize, then: O) -> MaybeNear<O, Noop> {
         MaybeNear::some_adjacent(states, count, then, Noop)
     }
 }
@@ -450,35 +640,24 @@ pub struct NotNear;
 
 impl NotNear {
     #[allow(clippy::new_ret_no_self)]
-    pub fn new<const S: usize, X: Step>(
-        states: StateQuery<S>,
+    pub fn new<X: Step>(
+        states: StateQuery,
         range: u32,
         count: usize,
         then: X,
-    ) -> MaybeNear<S, Noop, X> {
+    ) -> MaybeNear<Noop, X> {
         MaybeNear::new(states, range, count, Noop, then)
     }
 
-    pub fn any_adjacent<const S: usize, X: Step>(
-        states: StateQuery<S>,
-        then: X,
-    ) -> MaybeNear<S, Noop, X> {
+    pub fn any_adjacent<X: Step>(states: StateQuery, then: X) -> MaybeNear<Noop, X> {
         MaybeNear::any_adjacent(states, Noop, then)
     }
 
-    pub fn any<const S: usize, X: Step>(
-        states: StateQuery<S>,
-        range: u32,
-        then: X,
-    ) -> MaybeNear<S, Noop, X> {
+    pub fn any<X: Step>(states: StateQuery, range: u32, then: X) -> MaybeNear<Noop, X> {
         MaybeNear::any(states, range, Noop, then)
     }
 
-    pub fn some_adjacent<const S: usize, X: Step>(
-        states: StateQuery<S>,
-        count: usize,
-        then: X,
-    ) -> MaybeNear<S, Noop, X> {
+    pub fn some_adjacent<X: Step>(states: StateQuery, count: usize, then: X) -> MaybeNear<Noop, X> {
         MaybeNear::some_adjacent(states, count, Noop, then)
     }
 }
@@ -487,17 +666,17 @@ impl NotNear {
 /// otherwise apply `on_false`.
 pub struct If<C, T, F>(pub C, pub T, pub F)
 where
-    C: FnOnce(Hex, &BoardState, f32) -> bool,
+    C: FnOnce(Hex, &BoardState, &mut CellRng) -> bool,
     T: Step,
     F: Step;
 
 impl<C, T, F> Step for If<C, T, F>
 where
-    C: FnOnce(Hex, &BoardState, f32) -> bool,
+    C: FnOnce(Hex, &BoardState, &mut CellRng) -> bool,
     T: Step,
     F: Step,
 {
-    fn apply(self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
         if (self.0)(hex, states, rng) {
             self.1.apply(hex, states, rng)
         } else {
@@ -508,7 +687,7 @@ where
 
 impl<C, T, F> Debug for If<C, T, F>
 where
-    C: FnOnce(Hex, &BoardState, f32) -> bool,
+    C: FnOnce(Hex, &BoardState, &mut CellRng) -> bool,
     T: Step + Debug,
     F: Step + Debug,
 {
@@ -520,22 +699,22 @@ where
 /// Conditionally apply `on_true` when condition returns `true`.
 pub struct When<C, T>(pub C, pub T)
 where
-    C: FnOnce(Hex, &BoardState, f32) -> bool,
+    C: FnOnce(Hex, &BoardState, &mut CellRng) -> bool,
     T: Step;
 
 impl<C, T> Step for When<C, T>
 where
-    C: FnOnce(Hex, &BoardState, f32) -> bool,
+    C: FnOnce(Hex, &BoardState, &mut CellRng) -> bool,
     T: Step,
 {
-    fn apply(self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
         If(self.0, self.1, Noop).apply(hex, states, rng)
     }
 }
 
 impl<C, T> Debug for When<C, T>
 where
-    C: FnOnce(Hex, &BoardState, f32) -> bool,
+    C: FnOnce(Hex, &BoardState, &mut CellRng) -> bool,
     T: Step + Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -546,22 +725,22 @@ where
 /// Conditionally apply `on_false` when predicate returns `false`.
 pub struct Unless<C, F>(pub C, pub F)
 where
-    C: FnOnce(Hex, &BoardState, f32) -> bool,
+    C: FnOnce(Hex, &BoardState, &mut CellRng) -> bool,
     F: Step;
 
 impl<C, F> Step for Unless<C, F>
 where
-    C: FnOnce(Hex, &BoardState, f32) -> bool,
+    C: FnOnce(Hex, &BoardState, &mut CellRng) -> bool,
     F: Step,
 {
-    fn apply(self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
         If(self.0, Noop, self.1).apply(hex, states, rng)
     }
 }
 
 impl<C, F> Debug for Unless<C, F>
 where
-    C: FnOnce(Hex, &BoardState, f32) -> bool,
+    C: FnOnce(Hex, &BoardState, &mut CellRng) -> bool,
     F: Step + Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -571,76 +750,340 @@ where
 
 /// Try to swap with another cell `with_state` in some random `direction`.
 #[derive(Debug)]
-pub struct RandomSwap<const D: usize, const O: usize> {
+pub struct RandomSwap {
     /// The directions that are available to move in.
-    pub directions: Directions<D>,
+    pub directions: Directions,
 
     /// States that are available to swap with.
-    pub open: StateQuery<O>,
+    pub open: StateQuery,
+}
 
-    /// Max distance to move away from the start position.
-    pub distance: i32,
+impl RandomSwap {
+    pub fn adjacent(directions: impl Into<Directions>, open: StateQuery) -> Self {
+        Self {
+            directions: directions.into(),
+            open,
+        }
+    }
+}
 
-    /// When true, try the furthest it can through [`self.open`].
-    pub collide: bool,
+impl Step for RandomSwap {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let to = hex.neighbor(self.directions.choose(states.frame(hex), rng)?);
+        let from_id = states.get_next(hex)?;
+        let to_id = states.find_state(to, &self.open)?;
+        Some(BoardSlice::new(vec![(hex, to_id), (to, from_id)]))
+    }
 }
 
-impl<const D: usize, const O: usize> RandomSwap<D, O> {
-    pub fn adjacent(directions: Directions<D>, open: StateQuery<O>) -> Self {
+/// Swap with a neighboring fluid that is lighter, or heavier when
+/// rising, in some random `direction`.
+///
+/// Unlike [`RandomSwap`], the states that can be swapped with come
+/// from the [`Properties`] of each state instead of being listed.
+///
+/// [`Properties`]: crate::grid::cell::Properties
+#[derive(Debug)]
+pub struct Displace {
+    /// The directions that are available to move in.
+    pub directions: Directions,
+
+    /// When true, swap with heavier fluids instead of lighter ones.
+    pub rise: bool,
+}
+
+impl Displace {
+    /// Fall through anything lighter.
+    pub fn sink(directions: impl Into<Directions>) -> Self {
         Self {
-            directions,
-            open,
-            distance: 1,
-            collide: false,
+            directions: directions.into(),
+            rise: false,
+        }
+    }
+
+    /// Float up through anything heavier.
+    pub fn rise(directions: impl Into<Directions>) -> Self {
+        Self {
+            directions: directions.into(),
+            rise: true,
         }
     }
 }
 
-impl<const D: usize, const O: usize> Step for RandomSwap<D, O> {
-    fn apply(self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
-        let i = (rng * self.directions.len() as f32) as usize;
-        let direction = self.directions[i];
-        if self.collide {
-            let mut check_distance = 1;
-            let mut furthest: Option<((Hex, StateId), (Hex, StateId))> = None;
-            while check_distance <= self.distance {
-                if let Some(set) = self.in_direction(hex, direction, check_distance, states) {
-                    furthest = Some(set);
-                    check_distance += 1;
-                } else {
+impl Step for Displace {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let to = hex.neighbor(self.directions.choose(states.frame(hex), rng)?);
+        let from_id = states.get_next(hex)?;
+        let to_id = states.get_next(to)?;
+        let from = states.properties(from_id);
+        let other = states.properties(to_id);
+        let displaces = if self.rise {
+            other.phase.is_fluid() && from.density < other.density
+        } else {
+            sinks_through(from, other)
+        };
+        if displaces {
+            Some(BoardSlice::new(vec![(hex, to_id), (to, from_id)]))
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether a cell can fall through `other`.
+fn sinks_through(from: &Properties, other: &Properties) -> bool {
+    other.phase.is_fluid() && from.density > other.density
+}
+
+/// Fall with gravity, speeding up each tick and moving several hexes
+/// at once along the hex line of its velocity. Like [`Displace`], it
+/// passes through lighter fluids.
+///
+/// On impact the cell gives some of its velocity to what it hit, if
+/// that has room to move, and keeps some of its speed turned sideways
+/// as a splash. A cell with nowhere to fall does nothing, so the steps
+/// after it can move it instead.
+#[derive(Debug)]
+pub struct Fall {
+    /// Speed gained each tick, in hexes per tick.
+    pub acceleration: f32,
+    /// The fastest the cell can move, in hexes per tick.
+    pub terminal: f32,
+    /// How much of the speed is kept on impact, turned sideways.
+    pub splash: f32,
+    /// How much of the velocity is given to what it hits.
+    pub transfer: f32,
+}
+
+/// Splashes slower than this, in hexes per tick, are dropped so cells
+/// can come to rest.
+const SETTLE_SPEED: f32 = 1.0;
+
+impl Step for Fall {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let down = states.gravity(hex).down().unwrap_or(Vec2::ZERO);
+        let velocity =
+            (states.velocity(hex) + down * self.acceleration).clamp_length_max(self.terminal);
+        if velocity == Vec2::ZERO {
+            return None;
+        }
+        let from_id = states.get_next(hex)?;
+        let from = states.properties(from_id);
+
+        // Aim at a spot a little to either side, so cells falling
+        // between two hexes don't always pick the same one.
+        let layout = states.layout();
+        let origin = layout.hex_to_world_pos(hex);
+        let spacing = layout.hex_to_world_pos(hex.neighbor(EdgeDirection::default())) - origin;
+        let distance = velocity.length().max(1.0) * spacing.length();
+        let jitter = velocity.perp().normalize() * (rng.f32() - 0.5) * spacing.length();
+        let target = layout.world_pos_to_hex(origin + velocity.normalize() * distance + jitter);
+
+        let mut to = hex;
+        let mut hit = None;
+        for next in hex.line_to(target).skip(1) {
+            let Some(next_id) = states.get_next(next) else {
+                break;
+            };
+            if !sinks_through(from, states.properties(next_id)) {
+                hit = Some((next, next_id));
+                break;
+            }
+            to = next;
+        }
+
+        let Some((hit, hit_id)) = hit else {
+            if to == hex {
+                return None;
+            }
+            let to_id = states.get_next(to)?;
+            return Some(
+                BoardSlice::new(vec![(hex, to_id), (to, from_id)]).with_velocity(to, velocity),
+            );
+        };
+        if to == hex {
+            // Resting on something, so let other steps move it
+            return None;
+        }
+        let side = if rng.bool() { 1.0 } else { -1.0 };
+        let splash = down.perp() * side * velocity.length() * self.splash;
+        let splash = if splash.length() < SETTLE_SPEED {
+            Vec2::ZERO
+        } else {
+            splash
+        };
+        let mut slice = BoardSlice::new(vec![(hex, states.get_next(to)?), (to, from_id)])
+            .with_velocity(to, splash);
+        // Only push what has room to move, or resting cells would be
+        // kept moving by everything landing on them.
+        let beyond = nearest_edge(states, velocity).map(|direction| hit.neighbor(direction));
+        if self.transfer > 0.0
+            && states.properties(hit_id).phase != Phase::Solid
+            && beyond
+                .and_then(|beyond| states.get_next(beyond))
+                .is_some_and(|id| sinks_through(states.properties(hit_id), states.properties(id)))
+            && !states.any_set([hit])
+        {
+            slice.cells.push((hit, hit_id));
+            slice = slice.with_velocity(hit, states.velocity(hit) + velocity * self.transfer);
+        }
+        Some(slice)
+    }
+}
+
+/// Destroy everything within `radius` at once, and fling the cells
+/// just outside it away from the center.
+///
+/// Every hex it touches is claimed in a single [`BoardSlice`]. Hexes
+/// that were already written this tick are left alone.
+#[derive(Debug)]
+pub struct Blast {
+    pub radius: u32,
+    /// What destroyed cells turn into, picked at random for each.
+    pub into: StateSet,
+    /// States that are neither destroyed nor flung.
+    pub resist: StateQuery,
+    /// How many hexes debris is thrown through [`Air`].
+    pub fling: u32,
+}
+
+impl Step for Blast {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let free = |slice: &BoardSlice, at: Hex| {
+            states.get_current(at).is_some()
+                && !states.any_set([at])
+                && !states.is_state(at, &self.resist)
+                && !slice.iter().any(|(written, _id)| *written == at)
+        };
+
+        let mut slice = BoardSlice::EMPTY;
+        for at in hex.range(self.radius) {
+            if free(&slice, at) {
+                slice.cells.push((at, self.into.choose(rng)?));
+            }
+        }
+
+        let layout = states.layout();
+        let origin = layout.hex_to_world_pos(Hex::ZERO);
+        for at in hex.ring(self.radius + 1) {
+            if !free(&slice, at) {
+                continue;
+            }
+            let debris = states.get_next(at)?;
+            if debris == Air::id() {
+                continue;
+            }
+            let direction = hex.main_direction_to(at);
+            let mut to = at;
+            for _ in 0..self.fling {
+                let next = to.neighbor(direction);
+                if !free(&slice, next) || states.get_next(next) != Some(Air::id()) {
                     break;
                 }
+                to = next;
             }
-            furthest.map(|(from, to)| BoardSlice(vec![from, to]))
-        } else {
-            if let Some(slice) = self
-                .in_direction(hex, direction, self.distance, states)
-                .map(|(from, to)| BoardSlice(vec![from, to]))
-            {
-                return Some(slice);
+            if to != at {
+                slice.cells.push((at, Air::id()));
             }
-            None
+            slice.cells.push((to, debris));
+            let outward = (layout.hex_to_world_pos(direction.into_hex()) - origin).normalize();
+            slice = slice.with_velocity(to, outward * self.fling as f32);
         }
+        Some(slice)
     }
 }
 
-impl<const D: usize, const O: usize> RandomSwap<D, O> {
-    fn in_direction(
-        &self,
-        from: Hex,
-        direction: EdgeDirection,
-        distance: i32,
-        states: &BoardState,
-    ) -> Option<((Hex, StateId), (Hex, StateId))> {
-        let from_id = *states.get_current(from).unwrap();
-        let to = from + direction * distance;
-        if let Some(components) = states
-            .find_state(to, &self.open)
-            .map(|to_id| ((from, to_id), (to, from_id)))
-        {
-            return Some(components);
+/// Get pushed by the wind into a neighbor that is `open`.
+///
+/// The chance to move is the strength of the wind at the cell, and it
+/// moves along the edge that points closest to the wind.
+#[derive(Debug)]
+pub struct Blow {
+    pub open: StateQuery,
+}
+
+impl Step for Blow {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let wind = states.wind(hex);
+        if wind == Vec2::ZERO || rng.f32() >= wind.length() {
+            return None;
         }
-        None
+        let to = hex.neighbor(nearest_edge(states, wind)?);
+        let from_id = states.get_next(hex)?;
+        let to_id = states.find_state(to, &self.open)?;
+        Some(BoardSlice::new(vec![(hex, to_id), (to, from_id)]))
+    }
+}
+
+/// The edge that points closest to `towards`, in world space.
+fn nearest_edge(states: &BoardState, towards: Vec2) -> Option<EdgeDirection> {
+    EdgeDirection::ALL_DIRECTIONS.into_iter().max_by(|a, b| {
+        let a = world_direction(states, *a).dot(towards);
+        let b = world_direction(states, *b).dot(towards);
+        a.total_cmp(&b)
+    })
+}
+
+/// Where `direction` points in world space, on the layout of the board.
+fn world_direction(states: &BoardState, direction: EdgeDirection) -> Vec2 {
+    let layout = states.layout();
+    layout.hex_to_world_pos(direction.into_hex()) - layout.hex_to_world_pos(Hex::ZERO)
+}
+
+/// Build a step from the [`Directions`] that point with the wind at
+/// the cell, which are the headings whose edges are all less than a
+/// quarter turn from the wind. In calm air there are none.
+pub struct Downwind<F>(pub F);
+
+impl<F, S> Step for Downwind<F>
+where
+    F: FnOnce(Directions) -> S,
+    S: Step,
+{
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let wind = states.wind(hex);
+        let frame = states.frame(hex);
+        let directions = Heading::ALL
+            .into_iter()
+            .filter(|heading| {
+                let mut edges = frame.edges(*heading).peekable();
+                edges.peek().is_some()
+                    && edges.all(|edge| world_direction(states, edge).dot(wind) > 0.0)
+            })
+            .collect();
+        (self.0)(directions).apply(hex, states, rng)
+    }
+}
+
+impl<F> Debug for Downwind<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Downwind(..)")
+    }
+}
+
+/// Move into an `open` neighbor when the liquid this cell is connected
+/// to has a surface at least a row higher, so liquids rise through
+/// connected channels until they are level.
+///
+/// Only does anything when [`Pressure`] is enabled.
+///
+/// [`Pressure`]: crate::grid::Pressure
+#[derive(Debug)]
+pub struct Squeeze {
+    /// The directions that are available to move in.
+    pub directions: Directions,
+    pub open: StateQuery,
+}
+
+impl Step for Squeeze {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        if states.pressure(hex) < 1.0 {
+            return None;
+        }
+        let to = hex.neighbor(self.directions.choose(states.frame(hex), rng)?);
+        let from_id = states.get_next(hex)?;
+        let to_id = states.find_state(to, &self.open)?;
+        Some(BoardSlice::new(vec![(hex, to_id), (to, from_id)]))
     }
 }
 
@@ -651,13 +1094,13 @@ pub struct Swap {
 }
 
 impl Step for Swap {
-    fn apply(self, hex: Hex, states: &BoardState, _rng: f32) -> Option<BoardSlice> {
-        if states.any_set([hex, self.other]) {
+    fn apply(self, hex: Hex, states: &BoardState, _rng: &mut CellRng) -> Option<BoardSlice> {
+        if hex == self.other || states.any_set([hex, self.other]) {
             None
         } else {
-            Some(BoardSlice(vec![
-                (hex, *states.get_current(self.other).unwrap()),
-                (self.other, *states.get_current(hex).unwrap()),
+            Some(BoardSlice::new(vec![
+                (hex, *states.get_current(self.other)?),
+                (self.other, *states.get_current(hex)?),
             ]))
         }
     }
@@ -665,29 +1108,157 @@ impl Step for Swap {
 
 /// Set the state of a cell
 #[derive(Debug)]
-pub struct Set<const S: usize>(pub [StateId; S]);
+pub struct Set(pub StateSet);
 
-impl<const S: usize> Step for Set<S> {
-    fn apply(self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
+impl Step for Set {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
         if states.any_set([hex]) {
             None
         } else {
-            let i = (rng * self.0.len() as f32) as usize;
-            self.0.get(i).map(|id| BoardSlice(vec![(hex, *id)]))
+            self.0
+                .choose(rng)
+                .map(|id| BoardSlice::new(vec![(hex, id)]))
         }
     }
 }
 
+/// The copies of a [`Pattern`] that are tried besides the one that
+/// was written.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Symmetry {
+    /// Only the pattern as written.
+    #[default]
+    None,
+    /// Also flipped left to right.
+    Mirror,
+    /// All 6 rotations.
+    Rotate,
+    /// All 6 rotations of the pattern and its mirror image.
+    All,
+}
+
+impl Symmetry {
+    /// Every `(mirrored, clockwise rotations)` this symmetry expands to.
+    fn transforms(self) -> Vec<(bool, u32)> {
+        let mirrors: &[bool] = match self {
+            Symmetry::None | Symmetry::Rotate => &[false],
+            Symmetry::Mirror | Symmetry::All => &[false, true],
+        };
+        let rotations = match self {
+            Symmetry::None | Symmetry::Mirror => 0..1,
+            Symmetry::Rotate | Symmetry::All => 0..6,
+        };
+        mirrors
+            .iter()
+            .flat_map(|mirror| rotations.clone().map(|rotation| (*mirror, rotation)))
+            .collect()
+    }
+}
+
+/// What a [`Pattern`] writes to a hex.
+#[derive(Debug, Clone, Copy)]
+pub enum Rewrite {
+    /// One of the states, picked at random.
+    Set(StateSet),
+    /// The state at another offset of the pattern, so cells can be
+    /// moved around.
+    Copy(Offset),
+}
+
+/// Rewrite the neighborhood of a cell when it matches a template.
+///
+/// Offsets are relative to the cell and are usually within a radius
+/// of 1 or 2. The template is expanded by its [`Symmetry`] and the
+/// copies are tried in a random order until one matches, so a rule
+/// only has to be written for one side.
+///
+/// States are not changed by the symmetry unless they are listed in
+/// [`Self::mirrors`], so mirroring a pattern that writes [`Sapling`]
+/// still writes [`Sapling`].
+///
+/// [`Sapling`]: crate::grid::cell::Sapling
+#[derive(Debug)]
+pub struct Pattern {
+    /// Every offset and what has to be there for the pattern to match.
+    pub matches: Vec<(Offset, StateQuery)>,
+    /// What to write to each offset when the pattern matches.
+    pub writes: Vec<(Offset, Rewrite)>,
+    pub symmetry: Symmetry,
+    /// Pairs of states that are mirror images of each other, like
+    /// [`BranchLeft`] and [`BranchRight`]. They swap places in the
+    /// mirrored copies of the pattern, both in what is matched and in
+    /// what is written by [`Rewrite::Set`].
+    ///
+    /// [`BranchLeft`]: crate::grid::cell::BranchLeft
+    /// [`BranchRight`]: crate::grid::cell::BranchRight
+    pub mirrors: Vec<(StateId, StateId)>,
+}
+
+impl Pattern {
+    /// The mirror image of `id`, or `id` itself if it has none.
+    fn mirror(&self, id: StateId) -> StateId {
+        self.mirrors
+            .iter()
+            .find_map(|(a, b)| match id {
+                id if id == *a => Some(*b),
+                id if id == *b => Some(*a),
+                _ => None,
+            })
+            .unwrap_or(id)
+    }
+}
+
+impl Step for Pattern {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let frame = states.frame(hex);
+        let mut transforms = self.symmetry.transforms();
+        rng.shuffle(&mut transforms);
+        transforms.into_iter().find_map(|(mirrored, rotation)| {
+            let at = |offset: Offset| {
+                let offset = offset.resolve(frame)?;
+                let offset = if mirrored {
+                    frame.mirror(offset)
+                } else {
+                    offset
+                };
+                Some(hex + offset.rotate_cw(rotation))
+            };
+            let mirror = |id| if mirrored { self.mirror(id) } else { id };
+            if !self.matches.iter().all(|(offset, query)| {
+                at(*offset)
+                    .and_then(|at| states.get_next(at))
+                    .is_some_and(|id| states.matches(mirror(id), query))
+            }) {
+                return None;
+            }
+            self.writes
+                .iter()
+                .map(|(offset, rewrite)| {
+                    let id = match rewrite {
+                        Rewrite::Set(into) => mirror(into.choose(rng)?),
+                        Rewrite::Copy(from) => states.get_next(at(*from)?)?,
+                    };
+                    let to = at(*offset)?;
+                    // Never write off the board
+                    states.get_current(to)?;
+                    Some((to, id))
+                })
+                .collect::<Option<Vec<_>>>()
+                .map(BoardSlice::new)
+        })
+    }
+}
+
 /// Apply `then` while a path is `walkable` to `goal`.
 #[derive(Debug)]
-pub struct WhileConnected<const W: usize, const G: usize, S: Step> {
-    pub walkable: StateQuery<W>,
-    pub goal: StateQuery<G>,
+pub struct WhileConnected<S: Step> {
+    pub walkable: StateQuery,
+    pub goal: StateQuery,
     pub then: S,
 }
 
-impl<const W: usize, const G: usize, S: Step> Step for WhileConnected<W, G, S> {
-    fn apply(self, start: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
+impl<S: Step> Step for WhileConnected<S> {
+    fn apply(self, start: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
         dijkstra(
             &start,
             |hex| {
@@ -706,19 +1277,524 @@ impl<const W: usize, const G: usize, S: Step> Step for WhileConnected<W, G, S> {
     }
 }
 
+/// Turn into one of `into` when the number of neighbors that were
+/// `charged` at the start of the tick is within `count`.
+///
+/// Unlike [`Near`], changes made earlier in the same tick are ignored,
+/// so a charge moves exactly one hex per tick whatever order the cells
+/// are updated in.
+#[derive(Debug)]
+pub struct Conduct {
+    pub charged: StateQuery,
+    pub count: RangeInclusive<usize>,
+    pub into: StateSet,
+}
+
+impl Step for Conduct {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let count = hex
+            .all_neighbors()
+            .into_iter()
+            .filter(|neighbor| states.was_state(*neighbor, &self.charged))
+            .count();
+        if self.count.contains(&count) {
+            Set(self.into).apply(hex, states, rng)
+        } else {
+            None
+        }
+    }
+}
+
+/// Apply `then` when the `depth` cells straight up are all `by`, so
+/// a cell can react to the weight of what is piled on top of it.
+///
+/// On boards without an edge straight up, the column zigzags between
+/// the two edges either side of it.
+#[derive(Debug)]
+pub struct Buried<S: Step> {
+    pub depth: u32,
+    pub by: StateQuery,
+    pub then: S,
+}
+
+impl<S: Step> Step for Buried<S> {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let up = states.frame(hex).edges(Heading::Up).collect::<Vec<_>>();
+        if up.is_empty() {
+            return None;
+        }
+        let mut above = hex;
+        for i in 0..self.depth as usize {
+            above = above.neighbor(up[i % up.len()]);
+            if !states.is_state(above, &self.by) {
+                return None;
+            }
+        }
+        self.then.apply(hex, states, rng)
+    }
+}
+
+/// Remember one of the neighbors that isn't `ignore` as the material
+/// this cell emits, unless it already has [`Emitter`] settings.
+#[derive(Debug)]
+pub struct Remember {
+    pub ignore: StateQuery,
+    pub rate: f32,
+}
+
+impl Step for Remember {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        if states.emitter(hex).is_some() || states.any_set([hex]) {
+            return None;
+        }
+        let material = hex
+            .all_neighbors()
+            .into_iter()
+            .filter(|neighbor| !states.is_state(*neighbor, &self.ignore))
+            .filter_map(|neighbor| states.get_next(neighbor))
+            .collect::<StateSet>()
+            .choose(rng)?;
+        Some(
+            BoardSlice::new(vec![(hex, states.get_next(hex)?)]).with_data(
+                hex,
+                Emitter {
+                    material,
+                    rate: self.rate,
+                },
+            ),
+        )
+    }
+}
+
+/// Turn a neighbor that is `open` into the material of this cell's
+/// [`Emitter`], as often as its rate.
+#[derive(Debug)]
+pub struct Emit {
+    pub directions: Directions,
+    pub open: StateQuery,
+}
+
+impl Step for Emit {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let emitter = states.emitter(hex)?;
+        if rng.f32() >= emitter.rate {
+            return None;
+        }
+        Infect {
+            directions: self.directions,
+            open: self.open,
+            into: emitter.material.into(),
+        }
+        .apply(hex, states, rng)
+    }
+}
+
+/// Turn every neighbor made of the material of this cell's
+/// [`Emitter`] into [`Air`], as often as its rate.
+#[derive(Debug)]
+pub struct Absorb {
+    pub directions: Directions,
+}
+
+impl Step for Absorb {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let emitter = states.emitter(hex)?;
+        if rng.f32() >= emitter.rate {
+            return None;
+        }
+        let material = StateQuery::any(emitter.material);
+        let cells = self
+            .directions
+            .edges(states.frame(hex))
+            .map(|direction| hex.neighbor(direction))
+            .filter(|neighbor| states.is_state(*neighbor, &material) && !states.is_set(*neighbor))
+            .map(|neighbor| (neighbor, Air::id()))
+            .collect::<Vec<_>>();
+        if cells.is_empty() {
+            None
+        } else {
+            Some(BoardSlice::new(cells))
+        }
+    }
+}
+
+/// Which numbers of live neighbors a [`LifeRule`] applies to, as one
+/// bit for each count from 0 to 6.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct NeighborCounts(u8);
+
+impl NeighborCounts {
+    pub const fn new(counts: &[u8]) -> Self {
+        let mut bits = 0;
+        let mut i = 0;
+        while i < counts.len() {
+            bits |= 1 << counts[i];
+            i += 1;
+        }
+        Self(bits)
+    }
+
+    pub fn contains(&self, count: usize) -> bool {
+        count < 8 && self.0 & (1 << count) != 0
+    }
+}
+
+/// A birth and survival rule for [`Automaton`], like `B2/S34`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LifeRule {
+    /// Live neighbors that bring a dead cell to life.
+    pub birth: NeighborCounts,
+    /// Live neighbors that keep a live cell alive.
+    pub survival: NeighborCounts,
+}
+
+/// Run a [`LifeRule`] for a live cell, which dies into `dead` unless
+/// it survives and brings the `dead` cells around it to life.
+///
+/// Neighbors are counted as they were at the start of the tick, so use
+/// it with [`UpdateMode::Synchronous`]. Each dead cell is brought to
+/// life by only one of its live neighbors, so live cells never write
+/// the same hex.
+///
+/// [`UpdateMode::Synchronous`]: crate::grid::cell::UpdateMode::Synchronous
+#[derive(Debug)]
+pub struct Automaton {
+    pub rule: LifeRule,
+    pub dead: StateId,
+}
+
+impl Step for Automaton {
+    fn apply(self, hex: Hex, states: &BoardState, _rng: &mut CellRng) -> Option<BoardSlice> {
+        let id = *states.get_current(hex)?;
+        let alive = StateQuery::any(id);
+        let dead = StateQuery::any(self.dead);
+        let live_neighbors = |hex: Hex| {
+            hex.all_neighbors()
+                .into_iter()
+                .filter(|neighbor| states.was_state(*neighbor, &alive))
+                .collect::<Vec<_>>()
+        };
+
+        let mut cells = Vec::new();
+        if !self.rule.survival.contains(live_neighbors(hex).len()) {
+            cells.push((hex, self.dead));
+        }
+        for neighbor in hex.all_neighbors() {
+            if !states.was_state(neighbor, &dead) || states.is_set(neighbor) {
+                continue;
+            }
+            let parents = live_neighbors(neighbor);
+            let owner = parents.iter().min_by_key(|parent| (parent.x, parent.y));
+            if self.rule.birth.contains(parents.len()) && owner == Some(&hex) {
+                cells.push((neighbor, id));
+            }
+        }
+        if cells.is_empty() {
+            None
+        } else {
+            Some(BoardSlice::new(cells))
+        }
+    }
+}
+
+/// Move the [`Agent`] at `hex` into `to`, swapping with whatever is
+/// there and turning to face the way it went.
+fn move_agent(hex: Hex, to: Hex, states: &BoardState) -> Option<BoardSlice> {
+    let from_id = states.get_next(hex)?;
+    let to_id = states.get_next(to)?;
+    let agent = Agent {
+        heading: hex.main_direction_to(to),
+        ..states.agent(hex).unwrap_or_default()
+    };
+    Some(BoardSlice::new(vec![(hex, to_id), (to, from_id)]).with_data(to, agent))
+}
+
+/// Whether an agent at `hex` can step into `to`. It has to be `open`,
+/// and when `grounded`, next to something that isn't a fluid other
+/// than the agent itself.
+fn can_enter(hex: Hex, to: Hex, open: &StateQuery, grounded: bool, states: &BoardState) -> bool {
+    states.is_state(to, open)
+        && (!grounded
+            || to.all_neighbors().into_iter().any(|neighbor| {
+                neighbor != hex
+                    && states
+                        .get_next(neighbor)
+                        .is_some_and(|id| !states.properties(id).phase.is_fluid())
+            }))
+}
+
+/// Keep track of an [`Agent`]'s hunger, and take its data along
+/// wherever `then` moves it.
+///
+/// Hunger goes back to zero next to one of `needs`, and otherwise
+/// grows by one each tick. At `limit` the agent dies, turning into one of
+/// `remains`. Steps that know nothing about agents, like [`Fall`],
+/// can be used in `then` without the agent forgetting its state.
+#[derive(Debug)]
+pub struct Live<S: Step> {
+    pub needs: StateQuery,
+    pub limit: u16,
+    pub remains: StateSet,
+    pub then: S,
+}
+
+impl<S: Step> Step for Live<S> {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let id = states.get_next(hex)?;
+        let mut agent = states.agent(hex).unwrap_or_default();
+        agent.hunger = if hex
+            .all_neighbors()
+            .into_iter()
+            .any(|neighbor| states.is_state(neighbor, &self.needs))
+        {
+            0
+        } else {
+            agent.hunger.saturating_add(1)
+        };
+        if agent.hunger >= self.limit {
+            return Set(self.remains).apply(hex, states, rng);
+        }
+        let Some(mut slice) = self.then.apply(hex, states, rng) else {
+            if states.any_set([hex]) {
+                return None;
+            }
+            return Some(BoardSlice::new(vec![(hex, id)]).with_data(hex, agent));
+        };
+        let moved = slice.data.iter_mut().find_map(|(_hex, data)| match data {
+            CellData::Agent(moved) => Some(moved),
+            _ => None,
+        });
+        if let Some(moved) = moved {
+            moved.hunger = agent.hunger;
+        } else if let Some(&(to, _id)) = slice.iter().find(|(_hex, written)| *written == id) {
+            slice = slice.with_data(to, agent);
+        } else if !slice.iter().any(|(written, _id)| *written == hex) {
+            slice.cells.push((hex, id));
+            slice = slice.with_data(hex, agent);
+        }
+        Some(slice)
+    }
+}
+
+/// Count the ticks a cell has lived in its [`CellData::Age`], and take
+/// its age along wherever `then` moves it. At `lifetime` the cell
+/// turns into one of `into`.
+#[derive(Debug)]
+pub struct Age<S: Step> {
+    pub lifetime: u16,
+    pub into: StateSet,
+    pub then: S,
+}
+
+impl<S: Step> Step for Age<S> {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        // Already written this tick: the cell has moved on or been replaced
+        if states.any_set([hex]) {
+            return None;
+        }
+        let id = states.get_next(hex)?;
+        let age = states.age(hex).unwrap_or_default().saturating_add(1);
+        if age >= self.lifetime {
+            return Set(self.into).apply(hex, states, rng);
+        }
+        let age = CellData::Age(age);
+        // A move onto a hex claimed this tick would be rejected along
+        // with the age, so stay put and age in place instead
+        let slice = self
+            .then
+            .apply(hex, states, rng)
+            .filter(|slice| !states.any_set(slice.iter().map(|(hex, _id)| *hex)));
+        let Some(mut slice) = slice else {
+            return Some(BoardSlice::new(vec![(hex, id)]).with_data(hex, age));
+        };
+        if let Some(&(to, _id)) = slice.iter().find(|(_hex, written)| *written == id) {
+            slice = slice.with_data(to, age);
+        } else if !slice.iter().any(|(written, _id)| *written == hex) {
+            slice.cells.push((hex, id));
+            slice = slice.with_data(hex, age);
+        }
+        Some(slice)
+    }
+}
+
+/// Move an [`Agent`] one hex along the shortest path through `open`
+/// cells to the nearest `goal` at most `range` hexes away. Does
+/// nothing once it is next to one.
+#[derive(Debug)]
+pub struct Seek {
+    pub open: StateQuery,
+    /// Only walk along cells next to something solid.
+    pub grounded: bool,
+    pub goal: StateQuery,
+    pub range: u32,
+}
+
+impl Step for Seek {
+    fn apply(self, hex: Hex, states: &BoardState, _rng: &mut CellRng) -> Option<BoardSlice> {
+        let path = bfs(
+            &hex,
+            |at| {
+                at.all_neighbors().into_iter().filter(|to| {
+                    states.is_state(*to, &self.goal)
+                        || (to.unsigned_distance_to(hex) <= self.range
+                            && can_enter(hex, *to, &self.open, self.grounded, states))
+                })
+            },
+            |at| states.is_state(*at, &self.goal),
+        )?;
+        let next = *path.get(1)?;
+        if states.is_state(next, &self.goal) {
+            None
+        } else {
+            move_agent(hex, next, states)
+        }
+    }
+}
+
+/// Move an [`Agent`] on along its heading through `open` cells. When
+/// blocked, or now and then with a chance of `turn`, it heads off in
+/// a random direction it can go instead.
+#[derive(Debug)]
+pub struct Wander {
+    pub open: StateQuery,
+    /// Only walk along cells next to something solid.
+    pub grounded: bool,
+    pub turn: f32,
+}
+
+impl Step for Wander {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let heading = states.agent(hex).unwrap_or_default().heading;
+        let enter = |direction: EdgeDirection| {
+            can_enter(
+                hex,
+                hex.neighbor(direction),
+                &self.open,
+                self.grounded,
+                states,
+            )
+        };
+        let heading = if enter(heading) && rng.f32() >= self.turn {
+            heading
+        } else {
+            let open = EdgeDirection::ALL_DIRECTIONS
+                .into_iter()
+                .filter(|direction| enter(*direction))
+                .collect::<Vec<_>>();
+            if open.is_empty() {
+                return None;
+            }
+            open[rng.usize(..open.len())]
+        };
+        move_agent(hex, hex.neighbor(heading), states)
+    }
+}
+
+/// One of the neighbors in `directions` that is `open`, each as likely
+/// as the others.
+fn choose_neighbor(
+    hex: Hex,
+    directions: Directions,
+    open: &StateQuery,
+    states: &BoardState,
+    rng: &mut CellRng,
+) -> Option<Hex> {
+    let neighbors = directions
+        .edges(states.frame(hex))
+        .map(|direction| hex.neighbor(direction))
+        .filter(|neighbor| states.is_state(*neighbor, open))
+        .collect::<Vec<_>>();
+    if neighbors.is_empty() {
+        None
+    } else {
+        Some(neighbors[rng.usize(..neighbors.len())])
+    }
+}
+
+/// Eat a neighbor that is one of `food`, leaving [`Air`] in its place.
+/// Unlike [`Infect`], any neighbor that is food will do.
+#[derive(Debug)]
+pub struct Eat {
+    pub directions: Directions,
+    pub food: StateQuery,
+}
+
+impl Step for Eat {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let to = choose_neighbor(hex, self.directions, &self.food, states, rng)?;
+        Some(BoardSlice::new(vec![(to, Air::id())]))
+    }
+}
+
+/// Pick up a neighbor that is one of `items`, leaving [`Air`] in its
+/// place, unless the [`Agent`] is already carrying something.
+#[derive(Debug)]
+pub struct Grab {
+    pub directions: Directions,
+    pub items: StateQuery,
+}
+
+impl Step for Grab {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let agent = states.agent(hex).unwrap_or_default();
+        if agent.carrying.is_some() {
+            return None;
+        }
+        let to = choose_neighbor(hex, self.directions, &self.items, states, rng)?;
+        let item = states.get_next(to)?;
+        Some(
+            BoardSlice::new(vec![(to, Air::id()), (hex, states.get_next(hex)?)]).with_data(
+                hex,
+                Agent {
+                    carrying: Some(item),
+                    ..agent
+                },
+            ),
+        )
+    }
+}
+
+/// Set down what an [`Agent`] is carrying in a neighbor that is
+/// `open`.
+#[derive(Debug)]
+pub struct Put {
+    pub directions: Directions,
+    pub open: StateQuery,
+}
+
+impl Step for Put {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
+        let agent = states.agent(hex)?;
+        let item = agent.carrying?;
+        let to = hex.neighbor(self.directions.choose(states.frame(hex), rng)?);
+        if !states.is_state(to, &self.open) {
+            return None;
+        }
+        Some(
+            BoardSlice::new(vec![(to, item), (hex, states.get_next(hex)?)]).with_data(
+                hex,
+                Agent {
+                    carrying: None,
+                    ..agent
+                },
+            ),
+        )
+    }
+}
+
 /// Check if next to a cell in a state.
 #[derive(Debug)]
-pub struct NextTo<const D: usize, const N: usize, S: Step> {
-    pub directions: Directions<D>,
-    pub next: StateQuery<N>,
+pub struct NextTo<S: Step> {
+    pub directions: Directions,
+    pub next: StateQuery,
     pub step: S,
 }
 
-impl<const D: usize, const N: usize, S: Step> Step for NextTo<D, N, S> {
-    fn apply(self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
+impl<S: Step> Step for NextTo<S> {
+    fn apply(self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
         if self
             .directions
-            .into_iter()
+            .edges(states.frame(hex))
             .any(|direction| states.is_state(hex.neighbor(direction), &self.next))
         {
             self.step.apply(hex, states, rng)
@@ -727,3 +1803,6 @@ impl<const D: usize, const N: usize, S: Step> Step for NextTo<D, N, S> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests;
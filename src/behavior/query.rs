@@ -0,0 +1,341 @@
+use std::fmt::{self, Debug};
+
+use bevy_turborand::TurboRand;
+use hexx::{EdgeDirection, Hex, HexOrientation};
+use unique_type_id::TypeId;
+
+use super::StateId;
+use crate::{
+    grid::{cell::Tags, Gravity},
+    rng::CellRng,
+};
+
+/// A set of [`StateId`]s with one bit for each possible state.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StateSet([u64; 4]);
+
+impl StateSet {
+    pub const EMPTY: Self = Self([0; 4]);
+
+    pub fn contains(&self, id: StateId) -> bool {
+        self.0[id.0 as usize / 64] & (1 << (id.0 % 64)) != 0
+    }
+
+    pub fn insert(&mut self, id: StateId) {
+        self.0[id.0 as usize / 64] |= 1 << (id.0 % 64);
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.iter().map(|bits| bits.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::EMPTY
+    }
+
+    /// The `n`th state in order of [`StateId`].
+    pub fn nth(&self, mut n: usize) -> Option<StateId> {
+        for (i, bits) in self.0.iter().enumerate() {
+            let count = bits.count_ones() as usize;
+            if n < count {
+                let mut bits = *bits;
+                for _ in 0..n {
+                    // Clear the lowest set bit
+                    bits &= bits - 1;
+                }
+                return Some(TypeId((i * 64) as u8 + bits.trailing_zeros() as u8));
+            }
+            n -= count;
+        }
+        None
+    }
+
+    /// Pick a random state.
+    pub fn choose(&self, rng: &mut CellRng) -> Option<StateId> {
+        match self.len() {
+            0 => None,
+            len => self.nth(rng.usize(..len)),
+        }
+    }
+
+    /// Every state in order of [`StateId`].
+    pub fn iter(&self) -> impl Iterator<Item = StateId> + '_ {
+        self.0.iter().enumerate().flat_map(|(i, bits)| {
+            let mut bits = *bits;
+            std::iter::from_fn(move || {
+                if bits == 0 {
+                    return None;
+                }
+                let id = TypeId((i * 64) as u8 + bits.trailing_zeros() as u8);
+                // Clear the lowest set bit
+                bits &= bits - 1;
+                Some(id)
+            })
+        })
+    }
+}
+
+impl FromIterator<StateId> for StateSet {
+    fn from_iter<T: IntoIterator<Item = StateId>>(iter: T) -> Self {
+        let mut set = Self::EMPTY;
+        for id in iter {
+            set.insert(id);
+        }
+        set
+    }
+}
+
+impl<const S: usize> From<[StateId; S]> for StateSet {
+    fn from(ids: [StateId; S]) -> Self {
+        ids.into_iter().collect()
+    }
+}
+
+impl From<StateId> for StateSet {
+    fn from(id: StateId) -> Self {
+        [id].into()
+    }
+}
+
+impl Debug for StateSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// Which states a [`Step`](super::Step) is allowed to act on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateQuery {
+    Any(StateSet),
+    Except(StateSet),
+    /// Any state with all of the [`Tags`].
+    Tagged(Tags),
+}
+
+impl StateQuery {
+    pub fn any(states: impl Into<StateSet>) -> Self {
+        Self::Any(states.into())
+    }
+
+    pub fn except(states: impl Into<StateSet>) -> Self {
+        Self::Except(states.into())
+    }
+
+    pub const fn tagged(tags: Tags) -> Self {
+        Self::Tagged(tags)
+    }
+}
+
+/// A direction on screen, which is turned into the [`EdgeDirection`]s
+/// that point that way for the orientation of the board. The headings
+/// are turned with the [`Gravity`] of the cell by a [`Frame`].
+///
+/// Pointy boards have no edge straight up or down, and flat boards
+/// have none straight left or right. Those headings are split between
+/// the two edges on either side.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Heading {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Heading {
+    pub const ALL: [Self; 8] = [
+        Self::Up,
+        Self::UpRight,
+        Self::Right,
+        Self::DownRight,
+        Self::Down,
+        Self::DownLeft,
+        Self::Left,
+        Self::UpLeft,
+    ];
+
+    /// The edges that point this way.
+    pub fn edges(self, orientation: HexOrientation) -> &'static [EdgeDirection] {
+        use EdgeDirection as E;
+        use HexOrientation::*;
+        match (orientation, self) {
+            (Pointy, Heading::Up) => &[E::POINTY_TOP_LEFT, E::POINTY_TOP_RIGHT],
+            (Pointy, Heading::UpRight) => &[E::POINTY_TOP_RIGHT],
+            (Pointy, Heading::Right) => &[E::POINTY_RIGHT],
+            (Pointy, Heading::DownRight) => &[E::POINTY_BOTTOM_RIGHT],
+            (Pointy, Heading::Down) => &[E::POINTY_BOTTOM_LEFT, E::POINTY_BOTTOM_RIGHT],
+            (Pointy, Heading::DownLeft) => &[E::POINTY_BOTTOM_LEFT],
+            (Pointy, Heading::Left) => &[E::POINTY_LEFT],
+            (Pointy, Heading::UpLeft) => &[E::POINTY_TOP_LEFT],
+            (Flat, Heading::Up) => &[E::FLAT_TOP],
+            (Flat, Heading::UpRight) => &[E::FLAT_TOP_RIGHT],
+            (Flat, Heading::Right) => &[E::FLAT_TOP_RIGHT, E::FLAT_BOTTOM_RIGHT],
+            (Flat, Heading::DownRight) => &[E::FLAT_BOTTOM_RIGHT],
+            (Flat, Heading::Down) => &[E::FLAT_BOTTOM],
+            (Flat, Heading::DownLeft) => &[E::FLAT_BOTTOM_LEFT],
+            (Flat, Heading::Left) => &[E::FLAT_TOP_LEFT, E::FLAT_BOTTOM_LEFT],
+            (Flat, Heading::UpLeft) => &[E::FLAT_TOP_LEFT],
+        }
+    }
+
+    /// Left and right, which don't depend on gravity.
+    fn is_level(self) -> bool {
+        matches!(self, Heading::Left | Heading::Right)
+    }
+}
+
+/// How [`Heading`]s map onto the board around a cell, from the
+/// orientation of the board and the [`Gravity`] at the cell.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Frame {
+    pub orientation: HexOrientation,
+    pub gravity: Gravity,
+}
+
+impl Frame {
+    /// The edges that point along `heading`, turned with gravity.
+    pub fn edges(self, heading: Heading) -> impl Iterator<Item = EdgeDirection> {
+        let turns = match self.gravity.turns() {
+            Some(turns) => Some(turns),
+            None if heading.is_level() => Some(0),
+            None => None,
+        };
+        heading
+            .edges(self.orientation)
+            .iter()
+            .filter(move |_edge| turns.is_some())
+            .map(move |edge| edge.rotate_cw(turns.unwrap_or_default()))
+    }
+
+    /// The edge pointing exactly along `heading`, if there is one.
+    pub fn edge(self, heading: Heading) -> Option<EdgeDirection> {
+        let mut edges = self.edges(heading);
+        match (edges.next(), edges.next()) {
+            (Some(edge), None) => Some(edge),
+            _ => None,
+        }
+    }
+
+    /// Flip `hex` left to right, as seen with down at the bottom.
+    pub fn mirror(self, hex: Hex) -> Hex {
+        let turns = self.gravity.turns().unwrap_or_default().into();
+        let hex = hex.rotate_ccw(turns);
+        let hex = match self.orientation {
+            HexOrientation::Pointy => hex.reflect_y(),
+            HexOrientation::Flat => -hex.reflect_x(),
+        };
+        hex.rotate_cw(turns)
+    }
+}
+
+/// Where a cell is relative to another, either as a fixed [`Hex`] or
+/// as a [`Heading`] that depends on the orientation of the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Offset {
+    Hex(Hex),
+    /// The neighbor along a heading. Headings without a single edge,
+    /// like [`Heading::Up`] on a pointy board, never resolve.
+    Heading(Heading),
+}
+
+impl Offset {
+    pub fn resolve(self, frame: Frame) -> Option<Hex> {
+        match self {
+            Offset::Hex(hex) => Some(hex),
+            Offset::Heading(heading) => frame.edge(heading).map(Hex::from),
+        }
+    }
+}
+
+impl From<Hex> for Offset {
+    fn from(hex: Hex) -> Self {
+        Self::Hex(hex)
+    }
+}
+
+impl From<Heading> for Offset {
+    fn from(heading: Heading) -> Self {
+        Self::Heading(heading)
+    }
+}
+
+/// A set of [`Heading`]s with one bit for each heading.
+#[derive(Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Directions(u8);
+
+impl Directions {
+    pub const NONE: Self = Self(0);
+    pub const ALL: Self = Self(u8::MAX);
+
+    pub fn contains(&self, heading: Heading) -> bool {
+        self.0 & (1 << heading as u8) != 0
+    }
+
+    pub fn insert(&mut self, heading: Heading) {
+        self.0 |= 1 << heading as u8;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Heading> {
+        let directions = *self;
+        Heading::ALL
+            .into_iter()
+            .filter(move |heading| directions.contains(*heading))
+    }
+
+    /// Every edge pointing along one of the headings, without repeats.
+    pub fn edges(&self, frame: Frame) -> impl Iterator<Item = EdgeDirection> {
+        let edges = self.edge_bits(frame);
+        EdgeDirection::ALL_DIRECTIONS
+            .into_iter()
+            .filter(move |edge| edges & (1 << edge.index()) != 0)
+    }
+
+    /// Pick a random edge, each as likely as the others.
+    pub fn choose(&self, frame: Frame, rng: &mut CellRng) -> Option<EdgeDirection> {
+        match self.edge_bits(frame).count_ones() as usize {
+            0 => None,
+            count => self.edges(frame).nth(rng.usize(..count)),
+        }
+    }
+
+    fn edge_bits(&self, frame: Frame) -> u8 {
+        self.iter()
+            .flat_map(|heading| frame.edges(heading))
+            .fold(0, |edges, edge| edges | 1 << edge.index())
+    }
+}
+
+impl FromIterator<Heading> for Directions {
+    fn from_iter<T: IntoIterator<Item = Heading>>(iter: T) -> Self {
+        let mut directions = Self::NONE;
+        for heading in iter {
+            directions.insert(heading);
+        }
+        directions
+    }
+}
+
+impl<const D: usize> From<[Heading; D]> for Directions {
+    fn from(headings: [Heading; D]) -> Self {
+        headings.into_iter().collect()
+    }
+}
+
+impl From<Heading> for Directions {
+    fn from(heading: Heading) -> Self {
+        [heading].into()
+    }
+}
+
+impl Debug for Directions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
@@ -0,0 +1,958 @@
+//! Property tests for the [`Step`] combinators.
+//!
+//! Boards are filled with random states, and some of their hexes are
+//! already claimed in [`BoardState::next`] to mimic a tick that is
+//! partially done.
+
+use bevy_turborand::{GlobalRng, SeededCore};
+use hexx::{EdgeDirection, Hex, HexOrientation};
+use proptest::{collection::vec, prelude::*};
+use rayon::prelude::*;
+
+use super::*;
+use crate::grid::{
+    cell::{Air, Ash, CellRegistry, Sand, Steam, Stone, Tags, Water},
+    golden, tick_cells, BoardState, Gravity, GravityField, Schedule,
+};
+
+const RADIUS: u32 = 3;
+
+fn hex_count() -> usize {
+    Hex::range_count(RADIUS) as usize
+}
+
+/// Every registered state, in a stable order.
+fn all_states() -> Vec<StateId> {
+    let mut ids = CellRegistry::builtin().keys().copied().collect::<Vec<_>>();
+    ids.sort();
+    ids
+}
+
+/// Index into [`all_states`].
+fn state() -> impl Strategy<Value = usize> {
+    0..all_states().len()
+}
+
+/// Index into [`BoardState::positions`].
+fn position() -> impl Strategy<Value = usize> {
+    0..hex_count()
+}
+
+fn direction() -> impl Strategy<Value = EdgeDirection> {
+    prop::sample::select(EdgeDirection::ALL_DIRECTIONS.to_vec())
+}
+
+fn rng() -> impl Strategy<Value = CellRng> {
+    any::<u64>().prop_map(CellRng::with_seed)
+}
+
+fn heading() -> impl Strategy<Value = Heading> {
+    prop::sample::select(Heading::ALL.to_vec())
+}
+
+fn frame() -> impl Strategy<Value = Frame> {
+    prop::sample::select(frames())
+}
+
+fn directions() -> impl Strategy<Value = Directions> {
+    vec(heading(), 1..4).prop_map(Directions::from_iter)
+}
+
+/// A board filled with `cells` where each of `claimed` has already
+/// been written to this tick.
+#[derive(Debug, Clone)]
+struct Board {
+    cells: Vec<usize>,
+    claimed: Vec<(usize, usize)>,
+}
+
+impl Board {
+    fn build(&self) -> BoardState {
+        let ids = all_states();
+        let mut states = BoardState::new(RADIUS, &CellRegistry::builtin());
+        for (i, cell) in self.cells.iter().enumerate() {
+            states.set_next(states.positions[i], ids[*cell]);
+        }
+        states.commit();
+        for (i, cell) in &self.claimed {
+            states.set_next(states.positions[*i], ids[*cell]);
+        }
+        states
+    }
+}
+
+fn board() -> impl Strategy<Value = Board> {
+    (
+        vec(state(), hex_count()),
+        vec((position(), state()), 0..hex_count()),
+    )
+        .prop_map(|(cells, claimed)| Board { cells, claimed })
+}
+
+fn tags() -> impl Strategy<Value = Tags> {
+    prop::sample::select(vec![
+        Tags::NONE,
+        Tags::FLAMMABLE,
+        Tags::LIQUID,
+        Tags::ORGANIC,
+        Tags::GAS,
+        Tags::HOT,
+        Tags::IGNITES,
+        Tags::FLAMMABLE | Tags::ORGANIC,
+    ])
+}
+
+fn query() -> impl Strategy<Value = StateSet> {
+    vec(state(), 1..4).prop_map(|indices| {
+        let ids = all_states();
+        indices.into_iter().map(|i| ids[i]).collect()
+    })
+}
+
+/// Assert `slice` writes each hex at most once.
+fn assert_unique(slice: &BoardSlice) {
+    for (i, (hex, _id)) in slice.iter().enumerate() {
+        assert!(
+            slice.iter().skip(i + 1).all(|(other, _id)| other != hex),
+            "{hex:?} is written twice in {slice:?}"
+        );
+    }
+}
+
+/// Assert `slice` only moves states around without creating or
+/// destroying any of them.
+fn assert_conserved(states: &BoardState, slice: &BoardSlice) {
+    assert_unique(slice);
+    let mut before = slice
+        .iter()
+        .map(|(hex, _id)| states.get_next(*hex).unwrap())
+        .collect::<Vec<_>>();
+    let mut after = slice.iter().map(|(_hex, id)| *id).collect::<Vec<_>>();
+    before.sort();
+    after.sort();
+    assert_eq!(before, after, "{slice:?} does not conserve states");
+}
+
+fn assert_in_bounds(states: &BoardState, slice: &BoardSlice) {
+    for (hex, _id) in slice.iter() {
+        assert!(
+            states.bounds().is_in_bounds(*hex),
+            "{hex:?} is outside of the board in {slice:?}"
+        );
+    }
+}
+
+proptest! {
+    #[test]
+    fn random_swap_conserves_states(
+        board in board(),
+        at in position(),
+        directions in directions(),
+        open in query(),
+        mut rng in rng(),
+    ) {
+        let states = board.build();
+        let swap = RandomSwap::adjacent(directions, StateQuery::Any(open));
+        if let Some(slice) = swap.apply(states.positions[at], &states, &mut rng) {
+            assert_in_bounds(&states, &slice);
+            assert_conserved(&states, &slice);
+        }
+    }
+
+    #[test]
+    fn fall_conserves_states(
+        board in board(),
+        at in position(),
+        acceleration in 0.0..4.0f32,
+        splash in 0.0..1.0f32,
+        transfer in 0.0..1.0f32,
+        mut rng in rng(),
+    ) {
+        let states = board.build();
+        let fall = Fall {
+            acceleration,
+            terminal: 4.0,
+            splash,
+            transfer,
+        };
+        if let Some(slice) = fall.apply(states.positions[at], &states, &mut rng) {
+            assert_in_bounds(&states, &slice);
+            assert_conserved(&states, &slice);
+            for (hex, _velocity) in &slice.velocities {
+                prop_assert!(slice.iter().any(|(written, _id)| written == hex));
+            }
+        }
+    }
+
+    #[test]
+    fn blast_leaves_claimed_and_resisting_hexes(
+        board in board(),
+        at in position(),
+        radius in 0..3u32,
+        fling in 0..3u32,
+        into in query(),
+        resist in query(),
+        mut rng in rng(),
+    ) {
+        let states = board.build();
+        let blast = Blast {
+            radius,
+            into,
+            resist: StateQuery::Any(resist),
+            fling,
+        };
+        if let Some(slice) = blast.apply(states.positions[at], &states, &mut rng) {
+            assert_in_bounds(&states, &slice);
+            assert_unique(&slice);
+            for (hex, _id) in slice.iter() {
+                prop_assert!(!states.is_set(*hex));
+                prop_assert!(!resist.contains(*states.get_current(*hex).unwrap()));
+            }
+        }
+    }
+
+    #[test]
+    fn swap_conserves_states(board in board(), at in position(), other in position()) {
+        let states = board.build();
+        let swap = Swap {
+            other: states.positions[other],
+        };
+        if let Some(slice) = swap.apply(states.positions[at], &states, &mut CellRng::with_seed(0)) {
+            assert_in_bounds(&states, &slice);
+            assert_conserved(&states, &slice);
+        }
+    }
+
+    #[test]
+    fn swap_stays_on_board(board in board(), at in position(), direction in direction()) {
+        let states = board.build();
+        let hex = states.positions[at];
+        let swap = Swap {
+            other: hex + direction * (2 * RADIUS as i32 + 1),
+        };
+        prop_assert!(swap.apply(hex, &states, &mut CellRng::with_seed(0)).is_none());
+    }
+
+    #[test]
+    fn drag_conserves_states(
+        board in board(),
+        at in position(),
+        directions in directions(),
+        open in query(),
+        drag in query(),
+        mut rng in rng(),
+    ) {
+        let states = board.build();
+        let drag = Drag {
+            directions,
+            open: StateQuery::Any(open),
+            drag: StateQuery::Any(drag),
+        };
+        if let Some(slice) = drag.apply(states.positions[at], &states, &mut rng) {
+            assert_in_bounds(&states, &slice);
+            assert_conserved(&states, &slice);
+        }
+    }
+
+    #[test]
+    fn displace_only_swaps_with_lighter_fluids(
+        board in board(),
+        at in position(),
+        directions in directions(),
+        rise: bool,
+        mut rng in rng(),
+    ) {
+        let states = board.build();
+        let hex = states.positions[at];
+        if let Some(slice) = (Displace { directions, rise }).apply(hex, &states, &mut rng) {
+            assert_in_bounds(&states, &slice);
+            assert_conserved(&states, &slice);
+            prop_assert_eq!(slice.len(), 2);
+            let from = states.properties(states.get_next(hex).unwrap());
+            let to = states.properties(states.get_next(slice[1].0).unwrap());
+            prop_assert!(to.phase.is_fluid());
+            if rise {
+                prop_assert!(from.density < to.density);
+            } else {
+                prop_assert!(from.density > to.density);
+            }
+        }
+    }
+
+    #[test]
+    fn state_set_matches_list(ids in vec(any::<u8>(), 0..32), mut rng in rng()) {
+        let ids = ids.into_iter().map(TypeId).collect::<Vec<_>>();
+        let set = ids.iter().copied().collect::<StateSet>();
+        let mut list = ids.clone();
+        list.sort();
+        list.dedup();
+        prop_assert_eq!(set.len(), list.len());
+        prop_assert_eq!(set.iter().collect::<Vec<_>>(), list.clone());
+        for id in (0..=u8::MAX).map(TypeId) {
+            prop_assert_eq!(set.contains(id), list.contains(&id));
+        }
+        match set.choose(&mut rng) {
+            Some(id) => prop_assert!(list.contains(&id)),
+            None => prop_assert!(list.is_empty()),
+        }
+    }
+
+    #[test]
+    fn directions_match_list(
+        list in vec(heading(), 0..8),
+        frame in frame(),
+        mut rng in rng(),
+    ) {
+        let directions = list.iter().copied().collect::<Directions>();
+        for heading in Heading::ALL {
+            prop_assert_eq!(directions.contains(heading), list.contains(&heading));
+        }
+        let edges = list
+            .iter()
+            .flat_map(|heading| frame.edges(*heading))
+            .collect::<Vec<_>>();
+        prop_assert!(directions.edges(frame).all(|edge| edges.contains(&edge)));
+        match directions.choose(frame, &mut rng) {
+            Some(direction) => prop_assert!(edges.contains(&direction)),
+            None => prop_assert!(edges.is_empty()),
+        }
+    }
+
+    #[test]
+    fn blow_moves_with_the_wind(
+        board in board(),
+        at in position(),
+        open in query(),
+        angle in 0.0..std::f32::consts::TAU,
+        mut rng in rng(),
+    ) {
+        let mut states = board.build();
+        let wind = bevy::math::Vec2::from_angle(angle);
+        states.set_wind(vec![wind; hex_count()]);
+        let hex = states.positions[at];
+        if let Some(slice) = (Blow { open: StateQuery::Any(open) }).apply(hex, &states, &mut rng) {
+            assert_in_bounds(&states, &slice);
+            let layout = states.layout();
+            let step = layout.hex_to_world_pos(slice[1].0) - layout.hex_to_world_pos(hex);
+            prop_assert_eq!(slice[0].0, hex);
+            prop_assert!(step.normalize().dot(wind) >= 0.85);
+        }
+    }
+
+    #[test]
+    fn tagged_matches_registry(board in board(), at in position(), tags in tags()) {
+        let states = board.build();
+        let registry = CellRegistry::builtin();
+        let hex = states.positions[at];
+        let id = states.get_next(hex).unwrap();
+        let query = StateQuery::tagged(tags);
+        prop_assert_eq!(
+            states.is_state(hex, &query),
+            registry[&id].properties.tags.contains(tags)
+        );
+        prop_assert_eq!(states.find_state(hex, &query).is_some(), states.is_state(hex, &query));
+    }
+
+    #[test]
+    fn set_only_touches_itself(
+        board in board(),
+        at in position(),
+        into in query(),
+        mut rng in rng(),
+    ) {
+        let states = board.build();
+        let hex = states.positions[at];
+        if let Some(slice) = Set(into).apply(hex, &states, &mut rng) {
+            prop_assert_eq!(slice.len(), 1);
+            prop_assert_eq!(slice[0].0, hex);
+        }
+    }
+
+    #[test]
+    fn infect_only_touches_directions(
+        board in board(),
+        at in position(),
+        directions in directions(),
+        open in query(),
+        into in query(),
+        mut rng in rng(),
+    ) {
+        let states = board.build();
+        let hex = states.positions[at];
+        let infect = Infect {
+            directions,
+            open: StateQuery::Any(open),
+            into,
+        };
+        if let Some(slice) = infect.apply(hex, &states, &mut rng) {
+            assert_in_bounds(&states, &slice);
+            prop_assert_eq!(slice.len(), 1);
+            prop_assert!(directions
+                .edges(states.frame(hex))
+                .any(|direction| hex.neighbor(direction) == slice[0].0));
+        }
+    }
+
+    #[test]
+    fn all_applies_every_step(
+        board in board(),
+        at in position(),
+        directions in directions(),
+        open in query(),
+        into in query(),
+        mut rng in rng(),
+    ) {
+        let states = board.build();
+        let hex = states.positions[at];
+        let all = All((
+            Set(into),
+            Infect {
+                directions,
+                open: StateQuery::Any(open),
+                into,
+            },
+        ));
+        if let Some(slice) = all.apply(hex, &states, &mut rng) {
+            assert_unique(&slice);
+            prop_assert_eq!(slice.len(), 2);
+            prop_assert_eq!(slice[0].0, hex);
+            prop_assert!(directions
+                .edges(states.frame(hex))
+                .any(|direction| hex.neighbor(direction) == slice[1].0));
+        }
+    }
+
+    /// Parallel ticks never write the same hex twice: every hex the
+    /// applied slices wrote shows up once in the next board.
+    #[test]
+    fn parallel_tick_writes_each_hex_once(cells in vec(state(), hex_count()), seed: u64) {
+        let states = Board { cells, claimed: Vec::new() }.build();
+        let registry = CellRegistry::builtin();
+        let mut rng = GlobalRng::with_seed(seed);
+        let written = tick_cells(&states, &registry, None, &mut rng, Schedule::Parallel);
+        let set = states.positions.iter().filter(|hex| states.is_set(**hex)).count();
+        prop_assert_eq!(written, set);
+    }
+
+    #[test]
+    fn all_fails_on_collision(
+        board in board(),
+        at in position(),
+        a in query(),
+        b in query(),
+        mut rng in rng(),
+    ) {
+        let states = board.build();
+        let hex = states.positions[at];
+        prop_assert!(All((Set(a), Set(b))).apply(hex, &states, &mut rng).is_none());
+        prop_assert!(All(vec![Set(a), Set(b)]).apply(hex, &states, &mut rng).is_none());
+    }
+
+    #[test]
+    fn overwrite_later_step_wins(
+        board in board(),
+        at in position(),
+        a in query(),
+        b in query(),
+        mut rng in rng(),
+    ) {
+        let states = board.build();
+        let hex = states.positions[at];
+        let applies = !states.any_set([hex]) && !a.is_empty() && !b.is_empty();
+        let tuple = Overwrite((Set(a), Set(b))).apply(hex, &states, &mut rng);
+        let vec = Overwrite(vec![Set(a), Set(b)]).apply(hex, &states, &mut rng);
+        for slice in [tuple, vec] {
+            prop_assert_eq!(slice.is_some(), applies);
+            if let Some(slice) = slice {
+                assert_unique(&slice);
+                prop_assert_eq!(slice.len(), 1);
+                prop_assert_eq!(slice[0].0, hex);
+                prop_assert!(b.contains(slice[0].1));
+            }
+        }
+    }
+
+    /// A pattern of only [`Rewrite::Copy`]s that moves each of its
+    /// cells somewhere else in the pattern.
+    #[test]
+    fn pattern_copies_conserve_states(
+        board in board(),
+        at in position(),
+        offsets in prop::sample::subsequence(Hex::ZERO.range(2).collect::<Vec<_>>(), 2..6),
+        open in query(),
+        rotate in 1..6usize,
+        mut rng in rng(),
+    ) {
+        let states = board.build();
+        let pattern = Pattern {
+            matches: offsets
+                .iter()
+                .map(|offset| ((*offset).into(), StateQuery::Except(open)))
+                .collect(),
+            writes: offsets
+                .iter()
+                .zip(offsets.iter().cycle().skip(rotate % offsets.len()))
+                .map(|(to, from)| ((*to).into(), Rewrite::Copy((*from).into())))
+                .collect(),
+            symmetry: Symmetry::All,
+            mirrors: Vec::new(),
+        };
+        if let Some(slice) = pattern.apply(states.positions[at], &states, &mut rng) {
+            assert_in_bounds(&states, &slice);
+            assert_conserved(&states, &slice);
+        }
+    }
+
+    #[test]
+    fn behaviors_stay_on_board(board in board(), at in position(), mut rng in rng()) {
+        let states = board.build();
+        let registry = CellRegistry::builtin();
+        let hex = states.positions[at];
+        for entry in registry.values() {
+            if let Some(slice) = entry.behavior.tick(hex, &states, &mut rng) {
+                assert_unique(&slice);
+                assert_in_bounds(&states, &slice);
+            }
+        }
+    }
+
+    /// [`BoardState::apply`] is what `sim_system` uses to queue slices
+    /// from many threads at once, so no hex may be claimed twice.
+    #[test]
+    fn parallel_apply_claims_each_hex_once(
+        board in board(),
+        slices in vec(vec((position(), state()), 1..4), 1..64),
+    ) {
+        let states = board.build();
+        let ids = all_states();
+        let claimed = states.next.read().unwrap().keys().copied().collect::<Vec<_>>();
+        let applied = slices
+            .into_par_iter()
+            .map(|slice| {
+                let mut hexes = slice
+                    .into_iter()
+                    .map(|(i, id)| (states.positions[i], ids[id]))
+                    .collect::<Vec<_>>();
+                hexes.sort_by_key(|(hex, _id)| (hex.x, hex.y));
+                hexes.dedup_by_key(|(hex, _id)| *hex);
+                hexes
+            })
+            .filter(|slice| states.apply(BoardSlice::new(slice.clone())))
+            .collect::<Vec<_>>();
+
+        let mut written = applied
+            .iter()
+            .flatten()
+            .map(|(hex, _id)| *hex)
+            .chain(claimed)
+            .collect::<Vec<_>>();
+        let count = written.len();
+        written.sort_by_key(|hex| (hex.x, hex.y));
+        written.dedup();
+        prop_assert_eq!(written.len(), count);
+
+        let next = states.next.read().unwrap();
+        for (hex, id) in applied.iter().flatten() {
+            prop_assert_eq!(next.get(hex), Some(id));
+        }
+    }
+}
+
+#[test]
+fn weighted_picks_in_proportion() {
+    let states = BoardState::new(RADIUS, &CellRegistry::builtin());
+    let ids = all_states();
+    let weights = [1.0, 2.0, 7.0];
+    let mut rng = CellRng::with_seed(0);
+    let mut picks = [0i32; 3];
+    for _ in 0..10_000 {
+        let weighted = Weighted::new(
+            weights
+                .iter()
+                .zip(&ids)
+                .map(|(weight, id)| (*weight, Set((*id).into()))),
+        );
+        let slice = weighted.apply(Hex::ZERO, &states, &mut rng).unwrap();
+        picks[ids.iter().position(|id| *id == slice[0].1).unwrap()] += 1;
+    }
+    for (picks, expected) in picks.into_iter().zip([1000, 2000, 7000]) {
+        assert!(
+            (picks - expected).abs() < 200,
+            "Picked {picks} times, expected about {expected}"
+        );
+    }
+}
+
+#[test]
+fn weighted_falls_through() {
+    let states = BoardState::new(RADIUS, &CellRegistry::builtin());
+    let steps = || {
+        [
+            (1.0, Noop.boxed()),
+            // Too light to ever be chosen
+            (f32::MIN_POSITIVE, Set(all_states()[0].into()).boxed()),
+        ]
+    };
+    assert!(Weighted::new(steps())
+        .apply(Hex::ZERO, &states, &mut CellRng::with_seed(0))
+        .is_none());
+    assert!(Weighted::fall_through(steps())
+        .apply(Hex::ZERO, &states, &mut CellRng::with_seed(0))
+        .is_some());
+}
+
+/// Move a [`Sand`] into [`Air`] to its right.
+fn sand_moves_right(symmetry: Symmetry) -> Pattern {
+    Pattern {
+        matches: vec![
+            (Hex::ZERO.into(), StateQuery::any([Sand::id()])),
+            (Heading::Right.into(), StateQuery::any([Air::id()])),
+        ],
+        writes: vec![
+            (Hex::ZERO.into(), Rewrite::Copy(Heading::Right.into())),
+            (Heading::Right.into(), Rewrite::Copy(Hex::ZERO.into())),
+        ],
+        symmetry,
+        mirrors: Vec::new(),
+    }
+}
+
+#[test]
+fn pattern_expands_symmetry() {
+    let registry = CellRegistry::builtin();
+    let mut rng = CellRng::with_seed(0);
+    // Only open to the left
+    let states = golden::parse(&registry, " # #\n. S #\n # #");
+    let left = EdgeDirection::POINTY_LEFT.into_hex();
+    assert!(sand_moves_right(Symmetry::None)
+        .apply(Hex::ZERO, &states, &mut rng)
+        .is_none());
+    for symmetry in [Symmetry::Mirror, Symmetry::Rotate, Symmetry::All] {
+        let slice = sand_moves_right(symmetry)
+            .apply(Hex::ZERO, &states, &mut rng)
+            .unwrap();
+        assert_eq!(
+            slice.cells,
+            vec![(Hex::ZERO, Air::id()), (left, Sand::id())]
+        );
+    }
+
+    // Only open below, which isn't a mirror image of the right
+    let states = golden::parse(&registry, " # #\n# S #\n . #");
+    assert!(sand_moves_right(Symmetry::Mirror)
+        .apply(Hex::ZERO, &states, &mut rng)
+        .is_none());
+    assert!(sand_moves_right(Symmetry::Rotate)
+        .apply(Hex::ZERO, &states, &mut rng)
+        .is_some());
+}
+
+#[test]
+fn pattern_mirrors_paired_states() {
+    let registry = CellRegistry::builtin();
+    // Sand turns the Air to its right into Water, and mirrored, Stone
+    // turns the Air to its left into Steam
+    let pattern = || Pattern {
+        matches: vec![
+            (Hex::ZERO.into(), StateQuery::any([Sand::id()])),
+            (Heading::Right.into(), StateQuery::any([Air::id()])),
+        ],
+        writes: vec![(Heading::Right.into(), Rewrite::Set(Water::id().into()))],
+        symmetry: Symmetry::Mirror,
+        mirrors: vec![(Sand::id(), Stone::id()), (Water::id(), Steam::id())],
+    };
+    let left = EdgeDirection::POINTY_LEFT.into_hex();
+    let right = -left;
+    let states = golden::parse(&registry, " . .\n. # .\n . .");
+    for seed in 0..10 {
+        let slice = pattern()
+            .apply(Hex::ZERO, &states, &mut CellRng::with_seed(seed))
+            .unwrap();
+        assert_eq!(slice.cells, vec![(left, Steam::id())]);
+    }
+    let states = golden::parse(&registry, " . .\n. S .\n . .");
+    for seed in 0..10 {
+        let slice = pattern()
+            .apply(Hex::ZERO, &states, &mut CellRng::with_seed(seed))
+            .unwrap();
+        assert_eq!(slice.cells, vec![(right, Water::id())]);
+    }
+}
+
+/// Every orientation with every gravity.
+fn frames() -> Vec<Frame> {
+    [HexOrientation::Pointy, HexOrientation::Flat]
+        .into_iter()
+        .flat_map(|orientation| {
+            Gravity::ALL.map(|gravity| Frame {
+                orientation,
+                gravity,
+            })
+        })
+        .collect()
+}
+
+fn layout(orientation: HexOrientation) -> hexx::HexLayout {
+    BoardState::new(0, &CellRegistry::builtin())
+        .with_layout(orientation, 2.0)
+        .layout()
+        .clone()
+}
+
+/// The direction of `gravity` on screen, in degrees counterclockwise
+/// from the right.
+fn gravity_angle(gravity: Gravity) -> Option<f32> {
+    gravity.turns().map(|turns| -90.0 - 60.0 * turns as f32)
+}
+
+#[test]
+fn headings_resolve_to_nearest_edges() {
+    for frame in frames() {
+        let layout = layout(frame.orientation);
+        for (i, heading) in Heading::ALL.into_iter().enumerate() {
+            let mut edges = frame.edges(heading).collect::<Vec<_>>();
+            edges.sort_by_key(|edge| edge.index());
+            let Some(down) = gravity_angle(frame.gravity) else {
+                let mut expected = match heading {
+                    Heading::Left | Heading::Right => heading.edges(frame.orientation).to_vec(),
+                    _ => vec![],
+                };
+                expected.sort_by_key(|edge| edge.index());
+                assert_eq!(edges, expected, "{heading:?} in {frame:?}");
+                continue;
+            };
+            let angle = down + 180.0 - 45.0 * i as f32;
+            let nearest = EdgeDirection::ALL_DIRECTIONS
+                .into_iter()
+                .filter(|edge| {
+                    let pos = layout.hex_to_world_pos(edge.into_hex());
+                    let diff = (pos.y.atan2(pos.x).to_degrees() - angle).rem_euclid(360.0);
+                    diff.min(360.0 - diff) <= 30.0 + f32::EPSILON * 360.0
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(edges, nearest, "{heading:?} in {frame:?}");
+        }
+    }
+}
+
+#[test]
+fn mirror_flips_across_gravity() {
+    for frame in frames() {
+        let layout = layout(frame.orientation);
+        let down = gravity_angle(frame.gravity).unwrap_or(-90.0).to_radians();
+        let axis = bevy::math::vec2(down.cos(), down.sin());
+        for hex in Hex::ZERO.range(3) {
+            let pos = layout.hex_to_world_pos(hex);
+            let mirrored = layout.hex_to_world_pos(frame.mirror(hex));
+            assert!(
+                (mirrored - (2.0 * pos.dot(axis) * axis - pos)).length() < 1e-3,
+                "{hex:?} in {frame:?}"
+            );
+        }
+    }
+}
+
+/// Where the [`Sand`] on the board is.
+fn find_sand(states: &BoardState) -> Hex {
+    states
+        .positions
+        .iter()
+        .copied()
+        .find(|hex| states.get_current(*hex) == Some(&Sand::id()))
+        .unwrap()
+}
+
+#[test]
+fn sand_falls_with_gravity() {
+    let registry = CellRegistry::builtin();
+    for frame in frames() {
+        let mut states =
+            golden::parse(&registry, "  . . .\n . . . .\n. . S . .\n . . . .\n  . . .")
+                .with_layout(frame.orientation, 2.0);
+        states.set_gravity(frame.gravity, GravityField::default());
+        let layout = states.layout().clone();
+        let mut rng = bevy_turborand::GlobalRng::with_seed(0);
+        let mut sand = Hex::ZERO;
+        for _ in 0..2 {
+            golden::tick(&mut states, &registry, &mut rng);
+            let moved = find_sand(&states);
+            let step = layout.hex_to_world_pos(moved) - layout.hex_to_world_pos(sand);
+            match gravity_angle(frame.gravity) {
+                Some(down) => {
+                    let down = down.to_radians();
+                    let fall = step.dot(bevy::math::vec2(down.cos(), down.sin()));
+                    assert!(fall > 0.0, "Sand did not fall in {frame:?}");
+                }
+                None => assert_eq!(moved, sand, "Sand fell without gravity"),
+            }
+            sand = moved;
+        }
+    }
+}
+
+#[test]
+fn gravity_field_overrides_global() {
+    let registry = CellRegistry::builtin();
+    let mut states = golden::parse(&registry, "  . . .\n . . . .\n. . S . .\n . . . .\n  . . .");
+    states.set_gravity(
+        Gravity::Down,
+        GravityField {
+            regions: vec![(hexx::HexBounds::new(Hex::ZERO, 1), Gravity::Up)],
+        },
+    );
+    golden::tick(
+        &mut states,
+        &registry,
+        &mut bevy_turborand::GlobalRng::with_seed(0),
+    );
+    assert!(find_sand(&states).y < 0, "Sand did not rise in the field");
+}
+
+#[test]
+fn falling_speeds_up() {
+    let registry = CellRegistry::builtin();
+    let mut states = golden::parse(
+        &registry,
+        &[
+            "      S . . . . . .",
+            "     . . . . . . . .",
+            "    . . . . . . . . .",
+            "   . . . . . . . . . .",
+            "  . . . . . . . . . . .",
+            " . . . . . . . . . . . .",
+            ". . . . . . . . . . . . .",
+            " . . . . . . . . . . . .",
+            "  . . . . . . . . . . .",
+            "   . . . . . . . . . .",
+            "    . . . . . . . . .",
+            "     . . . . . . . .",
+            "      . . . . . . .",
+        ]
+        .join("\n"),
+    );
+    let mut rng = bevy_turborand::GlobalRng::with_seed(0);
+    let mut sand = find_sand(&states);
+    let mut drops = Vec::new();
+    for _ in 0..4 {
+        golden::tick(&mut states, &registry, &mut rng);
+        let fallen = find_sand(&states);
+        drops.push(fallen.y - sand.y);
+        sand = fallen;
+    }
+    assert_eq!(drops[0], 1, "Sand should start falling one hex at a time");
+    assert!(drops[3] > 1, "Sand did not speed up: {drops:?}");
+    assert!(states.velocity(sand).y < 0.0);
+}
+
+#[test]
+fn blast_flings_debris_outward() {
+    let registry = CellRegistry::builtin();
+    let mut states = BoardState::new(6, &registry);
+    for hex in Hex::ZERO.range(3) {
+        states.set_next(hex, Sand::id());
+    }
+    states.commit();
+    let blast = Blast {
+        radius: 2,
+        into: Air::id().into(),
+        resist: StateQuery::any([]),
+        fling: 2,
+    };
+    let slice = blast
+        .apply(Hex::ZERO, &states, &mut CellRng::with_seed(0))
+        .unwrap();
+    let sand = slice
+        .iter()
+        .filter(|(_hex, id)| *id == Sand::id())
+        .map(|(hex, _id)| *hex)
+        .collect::<Vec<_>>();
+    assert_eq!(sand.len(), 18, "Only the ring should survive: {slice:?}");
+    for hex in sand {
+        assert_eq!(hex.ulength(), 5, "{hex:?} was not flung all the way");
+        let velocity = slice
+            .velocities
+            .iter()
+            .find(|(moved, _velocity)| *moved == hex)
+            .unwrap()
+            .1;
+        let outward = states.layout().hex_to_world_pos(hex);
+        assert!(velocity.dot(outward) > 0.0, "{hex:?} flew inward");
+    }
+}
+
+#[test]
+fn live_keeps_agent_data_with_its_cell() {
+    let registry = CellRegistry::builtin();
+    let mut states = BoardState::new(3, &registry);
+    let agent = Agent {
+        heading: EdgeDirection::ALL_DIRECTIONS[2],
+        carrying: Some(Sand::id()),
+        hunger: 4,
+    };
+    states.set_next_data(Hex::ZERO, Sand::id(), agent);
+    states.commit();
+    let live = |limit| Live {
+        needs: StateQuery::any([]),
+        limit,
+        remains: Air::id().into(),
+        then: Fall {
+            acceleration: 1.0,
+            terminal: 1.0,
+            splash: 0.0,
+            transfer: 0.0,
+        },
+    };
+
+    let slice = live(10)
+        .apply(Hex::ZERO, &states, &mut CellRng::with_seed(0))
+        .unwrap();
+    let (moved, _id) = *slice.iter().find(|(_hex, id)| *id == Sand::id()).unwrap();
+    assert_ne!(moved, Hex::ZERO, "The agent did not fall");
+    let hungrier = Agent { hunger: 5, ..agent };
+    assert_eq!(slice.data, vec![(moved, CellData::Agent(hungrier))]);
+
+    let slice = live(5)
+        .apply(Hex::ZERO, &states, &mut CellRng::with_seed(0))
+        .unwrap();
+    assert_eq!(slice.cells, vec![(Hex::ZERO, Air::id())]);
+    assert!(slice.data.is_empty());
+}
+
+#[test]
+fn overwrite_moves_and_leaves_a_trail() {
+    let registry = CellRegistry::builtin();
+    let states = golden::parse(&registry, "  . . .\n . . . .\n. . S . .\n . . . .\n  . . .");
+    let step = Overwrite((
+        RandomSwap::adjacent(Directions::ALL, StateQuery::any([Air::id()])),
+        Set(Ash::id().into()),
+    ));
+    let slice = step
+        .apply(Hex::ZERO, &states, &mut CellRng::with_seed(0))
+        .unwrap();
+    assert_eq!(slice.len(), 2, "{slice:?}");
+    assert!(slice.contains(&(Hex::ZERO, Ash::id())));
+    let (moved, _id) = slice.iter().find(|(_hex, id)| *id == Sand::id()).unwrap();
+    assert_eq!(moved.unsigned_distance_to(Hex::ZERO), 1);
+}
+
+#[test]
+fn drag_pulls_the_cell_behind() {
+    let registry = CellRegistry::builtin();
+    let states = golden::parse(&registry, "  . . .\n . . . .\n. W S . .\n . . . .\n  . . .");
+    let drag = Drag {
+        directions: Heading::Right.into(),
+        open: StateQuery::any([Air::id()]),
+        drag: StateQuery::any([Water::id()]),
+    };
+    let slice = drag
+        .apply(Hex::ZERO, &states, &mut CellRng::with_seed(0))
+        .unwrap();
+    assert_eq!(
+        slice.cells,
+        vec![
+            (Hex::ZERO, Water::id()),
+            (Hex::new(1, 0), Sand::id()),
+            (Hex::new(-1, 0), Air::id()),
+        ]
+    );
+}
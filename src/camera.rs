@@ -6,7 +6,7 @@ pub struct Plugin;
 impl bevy::prelude::Plugin for Plugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(ClearColor(Color::rgb(0.01, 0.01, 0.01)));
-        app.add_plugins(PanCamPlugin::default());
+        app.add_plugins(PanCamPlugin);
         app.add_systems(Startup, setup);
     }
 }
@@ -0,0 +1,216 @@
+use bevy::prelude::*;
+
+use super::*;
+use crate::behavior::*;
+
+/// How acid moves, whether it's diluted or not.
+fn flow() -> impl Step {
+    (
+        Fall {
+            acceleration: 0.5,
+            terminal: 4.0,
+            splash: 0.5,
+            transfer: 0.25,
+        },
+        Displace::sink([Heading::Down, Heading::DownLeft, Heading::DownRight]),
+        Choose {
+            a: Displace::sink([Heading::Left, Heading::Right]),
+            b: RandomSwap::adjacent(
+                [Heading::Left, Heading::Right],
+                StateQuery::any([Acid::id(), DilutedAcid::id(), Water::id()]),
+            ),
+            chance: 0.9,
+        },
+    )
+}
+
+/// A liquid that eats through anything without enough
+/// [`StateInfo::ACID_RESISTANCE`], and is used up doing it.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Acid;
+
+impl StateInfo for Acid {
+    const NAME: &'static str = "Acid";
+    const COLOR: HexColor = HexColor::Noise {
+        base_color: Color::rgb(0.4, 1.0, 0.1),
+        offset_color: Color::Rgba {
+            red: 0.1,
+            green: 0.0,
+            blue: 0.1,
+            alpha: -0.2,
+        },
+        speed: Vec2::X,
+        scale: Vec2::splat(0.2),
+    };
+    const HIDDEN: bool = false;
+    const SYMBOL: char = 'A';
+    const DENSITY: f32 = 1.2;
+    const PHASE: Phase = Phase::Liquid;
+}
+
+impl Behavior for Acid {
+    fn tick(&self) -> impl Step {
+        (
+            Annihilate {
+                directions: Directions::ALL,
+                open: StateQuery::any([Water::id()]),
+                into: [DilutedAcid::id()].into(),
+            },
+            Dissolve {
+                directions: Directions::ALL,
+                strength: 0.5,
+                into: [ToxicGas::id(), Air::id()].into(),
+                spent: [Self::id(), Air::id()].into(),
+            },
+            flow(),
+        )
+    }
+}
+
+/// [`Acid`] mixed with [`Water`], which is weaker and turns back into
+/// water once it's used up.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct DilutedAcid;
+
+impl StateInfo for DilutedAcid {
+    const NAME: &'static str = "Diluted Acid";
+    const COLOR: HexColor = HexColor::Noise {
+        base_color: Color::rgb(0.3, 0.7, 0.6),
+        offset_color: Color::Rgba {
+            red: 0.0,
+            green: 0.0,
+            blue: 0.2,
+            alpha: -0.2,
+        },
+        speed: Vec2::X,
+        scale: Vec2::splat(0.2),
+    };
+    const HIDDEN: bool = false;
+    const SYMBOL: char = 'a';
+    const DENSITY: f32 = 1.1;
+    const PHASE: Phase = Phase::Liquid;
+}
+
+impl Behavior for DilutedAcid {
+    fn tick(&self) -> impl Step {
+        (
+            Dissolve {
+                directions: Directions::ALL,
+                strength: 0.1,
+                into: [ToxicGas::id(), Air::id()].into(),
+                spent: [Water::id()].into(),
+            },
+            flow(),
+        )
+    }
+}
+
+/// Given off by [`Acid`] as it dissolves things. Heavier than air, so
+/// it settles before it clears.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct ToxicGas;
+
+impl StateInfo for ToxicGas {
+    const NAME: &'static str = "Toxic Gas";
+    const COLOR: HexColor = HexColor::Static(Color::Rgba {
+        red: 0.6,
+        green: 0.8,
+        blue: 0.2,
+        alpha: 0.3,
+    });
+    const HIDDEN: bool = true;
+    const SYMBOL: char = 'x';
+    const DENSITY: f32 = 0.002;
+    const PHASE: Phase = Phase::Gas;
+}
+
+impl Behavior for ToxicGas {
+    fn tick(&self) -> impl Step {
+        (
+            Chance {
+                to: Set([Air::id()].into()),
+                chance: 0.02,
+            },
+            Blow {
+                open: StateQuery::any([Air::id()]),
+            },
+            Displace::sink([Heading::Down, Heading::DownLeft, Heading::DownRight]),
+            RandomSwap::adjacent(
+                [
+                    Heading::Left,
+                    Heading::Right,
+                    Heading::UpLeft,
+                    Heading::UpRight,
+                ],
+                StateQuery::any([Air::id()]),
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::golden::{assert_golden, count};
+
+    #[test]
+    fn eats_sand_before_stone() {
+        let states = assert_golden(
+            "acid_eats_sand_before_stone",
+            0,
+            20,
+            r"
+                   . . . .
+                  . A A A .
+                 . A A A A .
+                . S S # # # .
+                 . S S # # .
+                  # # # # #
+                   # # # #
+            ",
+        );
+        // All four Sand are gone while fewer of the 14 Stone are
+        assert_eq!(count(&states, Sand::id()), 0);
+        assert!(count(&states, Stone::id()) > 14 - 4);
+    }
+
+    #[test]
+    fn never_eats_glass() {
+        let states = assert_golden(
+            "acid_never_eats_glass",
+            1,
+            30,
+            r"
+                   . . . .
+                  . . . . .
+                 G A A A A G
+                G A A A A A G
+                 G A A A A G
+                  G G G G G
+                   G G G G
+            ",
+        );
+        assert_eq!(count(&states, Glass::id()), 15);
+    }
+
+    #[test]
+    fn dilutes_in_water() {
+        assert_golden(
+            "acid_dilutes_in_water",
+            2,
+            10,
+            r"
+                   . . . .
+                  . A A A .
+                 # . . . . #
+                # W W W W W #
+                 # W W W W #
+                  # W W W #
+                   # # # #
+            ",
+        );
+    }
+}
@@ -10,6 +10,30 @@ impl StateInfo for Air {
     const NAME: &'static str = "Air";
     const COLOR: HexColor = HexColor::Invisible;
     const HIDDEN: bool = false;
+    const SYMBOL: char = '.';
+    const DENSITY: f32 = 0.001;
+    const PHASE: Phase = Phase::Gas;
 }
 
 impl Behavior for Air {}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::golden::assert_golden;
+
+    #[test]
+    fn stays_still() {
+        assert_golden(
+            "air_stays_still",
+            0,
+            3,
+            r"
+                  . . .
+                 . . . .
+                . . . . .
+                 . . . .
+                  . . .
+            ",
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use bevy::prelude::*;
+
+use super::*;
+use crate::behavior::*;
+
+/// What's left of a burned tree. A light powder that seeds can sprout
+/// in.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Ash;
+
+impl StateInfo for Ash {
+    const NAME: &'static str = "Ash";
+    const COLOR: HexColor = HexColor::Noise {
+        base_color: Color::rgb(0.55, 0.55, 0.55),
+        offset_color: Color::Rgba {
+            red: 0.1,
+            green: 0.1,
+            blue: 0.1,
+            alpha: 0.0,
+        },
+        speed: Vec2::ZERO,
+        scale: Vec2::ONE,
+    };
+    const HIDDEN: bool = false;
+    const SYMBOL: char = '_';
+    const DENSITY: f32 = 0.4;
+    const PHASE: Phase = Phase::Powder;
+    const ACID_RESISTANCE: f32 = 0.1;
+}
+
+impl Behavior for Ash {
+    fn tick(&self) -> impl Step {
+        (
+            Blow {
+                open: StateQuery::any([Air::id()]),
+            },
+            Fall {
+                acceleration: 0.25,
+                terminal: 2.0,
+                splash: 0.0,
+                transfer: 0.0,
+            },
+            Displace::sink([Heading::Down, Heading::DownLeft, Heading::DownRight]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::golden::assert_golden;
+
+    #[test]
+    fn seed_grows_in_ash() {
+        assert_golden(
+            "seed_grows_in_ash",
+            1,
+            40,
+            r"
+                     . . . . . .
+                    . . . . . . .
+                   . . . . . . . .
+                  . . . . . . . . .
+                 . . . . . , . . . .
+                . . . . . . . . . . .
+                 _ _ _ _ _ _ _ _ _ _
+                  # # # # # # # # #
+                   # # # # # # # #
+                    # # # # # # #
+                     # # # # # #
+            ",
+        );
+    }
+}
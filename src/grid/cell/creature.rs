@@ -0,0 +1,317 @@
+use bevy::prelude::*;
+use hexx::EdgeDirection;
+
+use super::*;
+use crate::behavior::*;
+
+/// What a creature remembers from tick to tick.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Agent {
+    /// The way it last moved.
+    pub heading: EdgeDirection,
+    /// What it picked up, if anything.
+    pub carrying: Option<StateId>,
+    /// Ticks since its needs were last met.
+    pub hunger: u16,
+}
+
+/// Whether the agent at `hex` has gone `hunger` ticks without its
+/// needs being met.
+fn hungry(hunger: u16) -> impl FnOnce(Hex, &BoardState, &mut CellRng) -> bool {
+    move |hex, states, _rng| {
+        states
+            .agent(hex)
+            .is_some_and(|agent| agent.hunger >= hunger)
+    }
+}
+
+/// Whether the agent at `hex` is carrying anything.
+fn carrying(hex: Hex, states: &BoardState, _rng: &mut CellRng) -> bool {
+    states
+        .agent(hex)
+        .is_some_and(|agent| agent.carrying.is_some())
+}
+
+fn fall() -> Fall {
+    Fall {
+        acceleration: 0.25,
+        terminal: 2.0,
+        splash: 0.0,
+        transfer: 0.0,
+    }
+}
+
+/// Walks over and under anything solid, digging up [`Sand`] and
+/// carrying it off to drop somewhere else.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Ant;
+
+impl StateInfo for Ant {
+    const NAME: &'static str = "Ant";
+    const COLOR: HexColor = HexColor::Static(Color::rgb(0.35, 0.12, 0.05));
+    const HIDDEN: bool = false;
+    const SYMBOL: char = 'm';
+    const TAGS: Tags = Tags::FLAMMABLE;
+    const ACID_RESISTANCE: f32 = 0.3;
+}
+
+impl Behavior for Ant {
+    fn tick(&self) -> impl Step {
+        Live {
+            needs: StateQuery::any([Leaf::id(), Seed::id()]),
+            limit: 2000,
+            remains: Air::id().into(),
+            then: (
+                Grab {
+                    directions: Directions::ALL,
+                    items: StateQuery::any([Sand::id()]),
+                },
+                Chance {
+                    to: Put {
+                        directions: Directions::ALL,
+                        open: StateQuery::any([Air::id()]),
+                    },
+                    chance: 0.05,
+                },
+                Unless(
+                    carrying,
+                    Seek {
+                        open: StateQuery::any([Air::id()]),
+                        grounded: true,
+                        goal: StateQuery::any([Sand::id()]),
+                        range: 6,
+                    },
+                ),
+                Wander {
+                    open: StateQuery::any([Air::id()]),
+                    grounded: true,
+                    turn: 0.05,
+                },
+                fall(),
+            ),
+        }
+    }
+}
+
+/// Swims about in [`Water`], and suffocates if it spends too long out
+/// of it, flopping towards the nearest water it can reach.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Fish;
+
+impl StateInfo for Fish {
+    const NAME: &'static str = "Fish";
+    const COLOR: HexColor = HexColor::Static(Color::rgb(1.0, 0.55, 0.1));
+    const HIDDEN: bool = false;
+    const SYMBOL: char = 'f';
+    const ACID_RESISTANCE: f32 = 0.3;
+}
+
+impl Behavior for Fish {
+    fn tick(&self) -> impl Step {
+        Live {
+            needs: StateQuery::any([Water::id()]),
+            limit: 30,
+            remains: Air::id().into(),
+            then: (
+                Chance {
+                    to: Wander {
+                        open: StateQuery::any([Water::id()]),
+                        grounded: false,
+                        turn: 0.1,
+                    },
+                    chance: 0.5,
+                },
+                fall(),
+                Seek {
+                    open: StateQuery::any([Air::id()]),
+                    grounded: true,
+                    goal: StateQuery::any([Water::id()]),
+                    range: 3,
+                },
+            ),
+        }
+    }
+}
+
+/// Flies through [`Air`], and when it gets hungry goes looking for
+/// [`Seed`]s and [`Ant`]s to eat.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Bird;
+
+impl Bird {
+    fn food() -> StateQuery {
+        StateQuery::any([Seed::id(), Ant::id()])
+    }
+}
+
+impl StateInfo for Bird {
+    const NAME: &'static str = "Bird";
+    const COLOR: HexColor = HexColor::Static(Color::rgb(0.25, 0.3, 0.45));
+    const HIDDEN: bool = false;
+    const SYMBOL: char = 'v';
+    const DENSITY: f32 = 0.5;
+    const TAGS: Tags = Tags::FLAMMABLE;
+    const ACID_RESISTANCE: f32 = 0.3;
+}
+
+impl Behavior for Bird {
+    fn tick(&self) -> impl Step {
+        Live {
+            needs: Self::food(),
+            limit: 600,
+            remains: Air::id().into(),
+            then: (
+                Eat {
+                    directions: Directions::ALL,
+                    food: Self::food(),
+                },
+                When(
+                    hungry(100),
+                    Seek {
+                        open: StateQuery::any([Air::id()]),
+                        grounded: false,
+                        goal: Self::food(),
+                        range: 8,
+                    },
+                ),
+                Wander {
+                    open: StateQuery::any([Air::id()]),
+                    grounded: false,
+                    turn: 0.1,
+                },
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::golden::{self, assert_golden, count};
+
+    #[test]
+    fn ants_dig_sand() {
+        assert_golden(
+            "ants_dig_sand",
+            0,
+            60,
+            r"
+                     . . . . . .
+                    . . . . . . .
+                   . . . . . . . .
+                  . . . . . . . . .
+                 . . m . . . m . . .
+                S S S S S S S S S S S
+                 S S S S S S S S S S
+                  S S S S S S S S S
+                   S S S S S S S S
+                    # # # # # # #
+                     # # # # # #
+            ",
+        );
+    }
+
+    #[test]
+    fn fish_flop_back_to_water() {
+        assert_golden(
+            "fish_flop_back_to_water",
+            0,
+            40,
+            r"
+                     . . . . . .
+                    . . . . . . .
+                   . . . . . . . .
+                  . f . . . . . . .
+                 # # # # . . . . . .
+                . . . . . . . . . . .
+                 W W W W W W W W W W
+                  W W W W f W W W W
+                   W W W W W W W W
+                    # # # # # # #
+                     # # # # # #
+            ",
+        );
+    }
+
+    /// Run `board` for some ticks, starting every agent with `hunger`.
+    fn run(board: &str, hunger: u16, ticks: usize) -> BoardState {
+        golden::run(0, ticks, board, |states| {
+            let agents = states
+                .iter()
+                .filter(|(_hex, id)| [Ant::id(), Fish::id(), Bird::id()].contains(id))
+                .map(|(hex, id)| (hex, *id))
+                .collect::<Vec<_>>();
+            for (hex, id) in agents {
+                let agent = Agent {
+                    hunger,
+                    ..default()
+                };
+                states.set_next_data(hex, id, agent);
+            }
+        })
+    }
+
+    #[test]
+    fn ant_carries_what_it_digs() {
+        let board = r"
+               . . . .
+              . . . . .
+             . . . m . .
+            S S S S S S S
+             S S S S S S
+              # # # # #
+               # # # #
+        ";
+        let states = run(board, 0, 3);
+        let (hex, _id) = states.iter().find(|(_hex, id)| **id == Ant::id()).unwrap();
+        let agent = states.agent(hex).unwrap();
+        assert_eq!(agent.carrying, Some(Sand::id()));
+        assert_eq!(agent.hunger, 3);
+        assert_eq!(count(&states, Sand::id()), 12);
+    }
+
+    #[test]
+    fn fish_breathe_in_water() {
+        let stranded = r"
+               . . . .
+              . . . . .
+             . . . . . .
+            . . . f . . .
+             # # # # # #
+              # # # # #
+               # # # #
+        ";
+        assert_eq!(count(&run(stranded, 0, 29), Fish::id()), 1);
+        assert_eq!(count(&run(stranded, 0, 30), Fish::id()), 0);
+        let swimming = stranded.replace('.', "W");
+        assert_eq!(count(&run(&swimming, 0, 100), Fish::id()), 1);
+    }
+
+    #[test]
+    fn hungry_bird_finds_food() {
+        let board = r"
+                     . . . . . .
+                    . . . . . . .
+                   . . v . . . . .
+                  . . . . . . . . .
+                 . . . . . . . . . .
+                . . . . . . . . . . .
+                 . . . . . . . . . .
+                  . . . . . . . . .
+                   . . . . . . , .
+                    # # # # # # #
+                     # # # # # #
+        ";
+        let states = run(board, 100, 30);
+        assert_eq!(count(&states, Seed::id()), 0);
+        let (hex, _id) = states.iter().find(|(_hex, id)| **id == Bird::id()).unwrap();
+        assert!(states.agent(hex).unwrap().hunger < 100);
+
+        let starving = board.replace(',', ".");
+        assert_eq!(count(&run(&starving, 590, 9), Bird::id()), 1);
+        assert_eq!(count(&run(&starving, 590, 10), Bird::id()), 0);
+    }
+}
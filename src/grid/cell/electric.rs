@@ -0,0 +1,169 @@
+use bevy::prelude::*;
+
+use super::*;
+use crate::behavior::*;
+
+/// A conductor that carries [`Spark`]s, like the wire in Wireworld.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Metal;
+
+impl StateInfo for Metal {
+    const NAME: &'static str = "Metal";
+    const COLOR: HexColor = HexColor::Static(Color::rgb(0.55, 0.57, 0.6));
+    const HIDDEN: bool = false;
+    const SYMBOL: char = '=';
+    const DENSITY: f32 = 7.8;
+    const ACID_RESISTANCE: f32 = 0.7;
+}
+
+impl Behavior for Metal {
+    fn tick(&self) -> impl Step {
+        // Only carry a spark from one or two neighbors, so sparks
+        // meeting at a junction cancel out
+        Conduct {
+            charged: StateQuery::any([Spark::id(), Battery::id()]),
+            count: 1..=2,
+            into: [Spark::id()].into(),
+        }
+    }
+}
+
+/// Current running through [`Metal`].
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Spark;
+
+impl StateInfo for Spark {
+    const NAME: &'static str = "Spark";
+    const COLOR: HexColor = HexColor::Flickering {
+        base_color: Color::rgb(0.6, 0.9, 1.0),
+        offset_color: Color::WHITE,
+    };
+    const HIDDEN: bool = false;
+    const SYMBOL: char = '$';
+    const DENSITY: f32 = 7.8;
+    const TAGS: Tags = Tags::IGNITES;
+}
+
+impl Behavior for Spark {
+    fn tick(&self) -> impl Step {
+        let cool = || Set([HotMetal::id()].into());
+        (
+            All((
+                cool(),
+                Infect {
+                    directions: Directions::ALL,
+                    open: StateQuery::tagged(Tags::FLAMMABLE),
+                    into: [Ember::id()].into(),
+                },
+            )),
+            All((
+                cool(),
+                Infect {
+                    directions: Directions::ALL,
+                    open: StateQuery::any([Water::id()]),
+                    into: [Steam::id()].into(),
+                },
+            )),
+            cool(),
+        )
+    }
+}
+
+/// [`Metal`] that just carried a [`Spark`] and can't carry another
+/// until it cools down, which keeps sparks moving forward.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct HotMetal;
+
+impl StateInfo for HotMetal {
+    const NAME: &'static str = "Hot Metal";
+    const COLOR: HexColor = HexColor::Static(Color::rgb(0.8, 0.45, 0.3));
+    const HIDDEN: bool = true;
+    const SYMBOL: char = '-';
+    const DENSITY: f32 = 7.8;
+    const TAGS: Tags = Tags::HOT;
+    const ACID_RESISTANCE: f32 = 0.7;
+}
+
+impl Behavior for HotMetal {
+    fn tick(&self) -> impl Step {
+        Set([Metal::id()].into())
+    }
+}
+
+/// Sparks every [`Metal`] next to it whenever the metal is cool.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Battery;
+
+impl StateInfo for Battery {
+    const NAME: &'static str = "Battery";
+    const COLOR: HexColor = HexColor::Static(Color::rgb(0.2, 0.6, 0.25));
+    const HIDDEN: bool = false;
+    const SYMBOL: char = '+';
+    const DENSITY: f32 = 3.0;
+    const ACID_RESISTANCE: f32 = 0.8;
+}
+
+impl Behavior for Battery {}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::golden::assert_golden;
+
+    #[test]
+    fn battery_pulses_along_wire() {
+        assert_golden(
+            "battery_pulses_along_wire",
+            0,
+            7,
+            r"
+                   . . . .
+                  . . . . .
+                 . . . . . .
+                + = = = = = =
+                 . . . . . .
+                  . . . . .
+                   . . . .
+            ",
+        );
+    }
+
+    #[test]
+    fn spark_only_moves_forward() {
+        assert_golden(
+            "spark_only_moves_forward",
+            0,
+            2,
+            r"
+                   . . . .
+                  . . . . .
+                 . . . . . .
+                = = - $ = = =
+                 . . . . . .
+                  . . . . .
+                   . . . .
+            ",
+        );
+    }
+
+    #[test]
+    fn spark_boils_and_burns() {
+        assert_golden(
+            "spark_boils_and_burns",
+            2,
+            12,
+            r"
+                   . . . .
+                  . . . . .
+                 W W W W W W
+                + = = = = = =
+                 , , , , , ,
+                  # # # # #
+                   # # # #
+            ",
+        );
+    }
+}
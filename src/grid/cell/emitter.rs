@@ -0,0 +1,159 @@
+use bevy::prelude::*;
+
+use super::*;
+use crate::behavior::*;
+
+/// What an emitting cell makes or removes, and the chance each tick
+/// that it does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Emitter {
+    pub material: StateId,
+    pub rate: f32,
+}
+
+/// States a [`Cloner`] never copies.
+fn not_cloned() -> StateQuery {
+    StateQuery::any([
+        Air::id(),
+        Cloner::id(),
+        Spout::id(),
+        Drain::id(),
+        Void::id(),
+    ])
+}
+
+/// Remembers the first material to touch it, and keeps making more of
+/// it in the air around it.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Cloner;
+
+impl StateInfo for Cloner {
+    const NAME: &'static str = "Clone";
+    const COLOR: HexColor = HexColor::Static(Color::rgb(0.75, 0.65, 0.1));
+    const HIDDEN: bool = false;
+    const SYMBOL: char = 'C';
+    const DENSITY: f32 = 0.0;
+}
+
+impl Behavior for Cloner {
+    fn tick(&self) -> impl Step {
+        (
+            Remember {
+                ignore: not_cloned(),
+                rate: 1.0,
+            },
+            Emit {
+                directions: Directions::ALL,
+                open: StateQuery::any([Air::id()]),
+            },
+        )
+    }
+}
+
+/// Makes a material it was painted with, at the rate it was painted
+/// with.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Spout;
+
+impl StateInfo for Spout {
+    const NAME: &'static str = "Spout";
+    const COLOR: HexColor = HexColor::Static(Color::rgb(0.2, 0.55, 0.75));
+    const HIDDEN: bool = true;
+    const SYMBOL: char = 'P';
+    const DENSITY: f32 = 0.0;
+}
+
+impl Behavior for Spout {
+    fn tick(&self) -> impl Step {
+        Emit {
+            directions: Directions::ALL,
+            open: StateQuery::any([Air::id()]),
+        }
+    }
+}
+
+/// Removes the material it was painted with from around it, and
+/// nothing else.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Drain;
+
+impl StateInfo for Drain {
+    const NAME: &'static str = "Drain";
+    const COLOR: HexColor = HexColor::Static(Color::rgb(0.15, 0.15, 0.3));
+    const HIDDEN: bool = true;
+    const SYMBOL: char = 'R';
+    const DENSITY: f32 = 0.0;
+}
+
+impl Behavior for Drain {
+    fn tick(&self) -> impl Step {
+        Absorb {
+            directions: Directions::ALL,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hexx::Hex;
+
+    use super::*;
+    use crate::grid::golden::{self, assert_golden, count};
+
+    #[test]
+    fn clone_copies_what_touches_it() {
+        assert_golden(
+            "clone_copies_what_touches_it",
+            0,
+            6,
+            r"
+                   . . . .
+                  . . W . .
+                 . . . . . .
+                . . . C . . .
+                 . . . . . .
+                  . . . . .
+                   # # # #
+            ",
+        );
+    }
+
+    /// A board of `fill` with a single `emitter` of `material` in the
+    /// middle, after some ticks.
+    fn run(fill: &str, emitter: StateId, material: StateId, rate: f32) -> BoardState {
+        let rows = [
+            "   . . . .",
+            "  . . . . .",
+            " . . . . . .",
+            ". . . . . . .",
+            " . . . . . .",
+            "  . . . . .",
+            "   . . . .",
+        ];
+        golden::run(0, 10, &rows.join("\n").replace('.', fill), |states| {
+            states.set_next_data(Hex::ZERO, emitter, Emitter { material, rate });
+        })
+    }
+
+    #[test]
+    fn spout_emits_at_its_rate() {
+        let states = run(".", Spout::id(), Sand::id(), 1.0);
+        assert!(count(&states, Sand::id()) > 5);
+        assert_eq!(states.emitter(Hex::ZERO).unwrap().material, Sand::id());
+        let states = run(".", Spout::id(), Sand::id(), 0.0);
+        assert_eq!(count(&states, Sand::id()), 0);
+    }
+
+    #[test]
+    fn drain_only_removes_its_material() {
+        let states = run("#", Drain::id(), Stone::id(), 1.0);
+        for neighbor in Hex::ZERO.all_neighbors() {
+            assert_eq!(states.get_current(neighbor), Some(&Air::id()));
+        }
+        let states = run("#", Drain::id(), Water::id(), 1.0);
+        assert_eq!(count(&states, Stone::id()), 36);
+    }
+}
@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+
+use super::*;
+use crate::behavior::*;
+
+/// Anything touching one of these sets off an explosive.
+fn ignition() -> StateQuery {
+    StateQuery::any([Fire::id(), Ember::id()])
+}
+
+/// The blast of an explosive, which leaves fire that can set off any
+/// explosives nearby.
+fn blast(radius: u32, fling: u32) -> Blast {
+    Blast {
+        radius,
+        into: [Fire::id(), Smoke::id(), Air::id()].into(),
+        resist: StateQuery::any([Stone::id(), Void::id()]),
+        fling,
+    }
+}
+
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Gunpowder;
+
+impl StateInfo for Gunpowder {
+    const NAME: &'static str = "Gunpowder";
+    const COLOR: HexColor = HexColor::Noise {
+        base_color: Color::rgb(0.2, 0.2, 0.22),
+        offset_color: Color::Rgba {
+            red: 0.1,
+            green: 0.1,
+            blue: 0.1,
+            alpha: 0.0,
+        },
+        speed: Vec2::ZERO,
+        scale: Vec2::ONE,
+    };
+    const HIDDEN: bool = false;
+    const SYMBOL: char = '%';
+    const DENSITY: f32 = 1.5;
+    const PHASE: Phase = Phase::Powder;
+    const ACID_RESISTANCE: f32 = 0.3;
+}
+
+impl Behavior for Gunpowder {
+    fn tick(&self) -> impl Step {
+        (
+            Near::any_adjacent(ignition(), blast(2, 3)),
+            Fall {
+                acceleration: 0.5,
+                terminal: 4.0,
+                splash: 0.0,
+                transfer: 0.5,
+            },
+            Displace::sink([Heading::Down, Heading::DownLeft, Heading::DownRight]),
+        )
+    }
+}
+
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Tnt;
+
+impl StateInfo for Tnt {
+    const NAME: &'static str = "TNT";
+    const COLOR: HexColor = HexColor::Static(Color::CRIMSON);
+    const HIDDEN: bool = false;
+    const SYMBOL: char = '!';
+    const DENSITY: f32 = 1.6;
+    const ACID_RESISTANCE: f32 = 0.6;
+}
+
+impl Behavior for Tnt {
+    fn tick(&self) -> impl Step {
+        Near::any_adjacent(ignition(), blast(4, 3))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::golden::{assert_golden, count};
+
+    #[test]
+    fn gunpowder_chains() {
+        assert_golden(
+            "gunpowder_chains",
+            0,
+            4,
+            r"
+                     . . . . . .
+                    . . . . . . .
+                   . . . . . . . .
+                  . . . . . . . . .
+                 . . . . . . . . . .
+                . . . . . . . . . . .
+                 . . . . . . . . . .
+                  . . . . . . . . .
+                   . % % % % % % %
+                    F % % % % % %
+                     # # # # # #
+            ",
+        );
+    }
+
+    #[test]
+    fn tnt_clears_sand() {
+        let states = assert_golden(
+            "tnt_clears_sand",
+            1,
+            3,
+            r"
+                       . . . . . . . .
+                      . . . . . . . . .
+                     . . . . . . . . . .
+                    . . . . . . . . . . .
+                   . . . . . . . . . . . .
+                  . . . . . . . . . . . . .
+                 . . . . . . . . . . . . . .
+                . . . . . . . . . . . . . . .
+                 . . . . . . . . . . . . . .
+                  . . . . . . . . . . . . .
+                   . . . . . . . . . . . .
+                    . . . . . . . . . . .
+                     S S S S S S S S S S
+                      S E ! S S S S S S
+                       # # # # # # # #
+            ",
+        );
+        assert!(count(&states, Sand::id()) < 17);
+    }
+}
@@ -1,8 +1,7 @@
 use bevy::prelude::*;
-use hexx::EdgeDirection;
 
 use super::*;
-use crate::behavior::{StateQuery::*, *};
+use crate::behavior::*;
 
 #[derive(UniqueTypeId)]
 #[UniqueTypeIdType = "u8"]
@@ -15,56 +14,60 @@ impl StateInfo for Fire {
         offset_color: Color::ORANGE,
     };
     const HIDDEN: bool = false;
+    const SYMBOL: char = 'F';
+    const DENSITY: f32 = 0.0003;
+    const PHASE: Phase = Phase::Gas;
+    const TAGS: Tags = Tags::HOT.union(Tags::IGNITES);
 }
 
 impl Behavior for Fire {
     fn tick(&self) -> impl Step {
         (
-            QueryTest(Any([Air::id()])),
+            QueryTest(StateQuery::any([Air::id()])),
+            // Burn out
             Chance {
-                to: Set([Air::id()]),
+                to: Set([Air::id(), Smoke::id()].into()),
                 chance: 0.1,
             },
-            Chance {
-                to: Infect {
-                    directions: EdgeDirection::ALL_DIRECTIONS,
-                    open: Any([
-                        Seed::id(),
-                        Sapling::id(),
-                        Trunk::id(),
-                        DeadTrunk::id(),
-                        BranchLeft::id(),
-                        BranchRight::id(),
-                        Twig::id(),
-                        Leaf::id(),
-                    ]),
-                    into: [Ember::id()],
-                },
-                chance: 0.5,
-            },
+            ignite(0.5),
             Annihilate {
                 directions: [
-                    EdgeDirection::POINTY_LEFT,
-                    EdgeDirection::POINTY_RIGHT,
-                    EdgeDirection::POINTY_TOP_LEFT,
-                    EdgeDirection::POINTY_TOP_RIGHT,
-                ],
-                open: Any([Water::id()]),
-                into: [Steam::id()],
+                    Heading::Left,
+                    Heading::Right,
+                    Heading::Up,
+                    Heading::UpLeft,
+                    Heading::UpRight,
+                ]
+                .into(),
+                open: StateQuery::any([Water::id()]),
+                into: [Steam::id()].into(),
             },
             RandomSwap::adjacent(
                 [
-                    EdgeDirection::POINTY_LEFT,
-                    EdgeDirection::POINTY_RIGHT,
-                    EdgeDirection::POINTY_TOP_LEFT,
-                    EdgeDirection::POINTY_TOP_RIGHT,
+                    Heading::Left,
+                    Heading::Right,
+                    Heading::Up,
+                    Heading::UpLeft,
+                    Heading::UpRight,
                 ],
-                Any([Air::id(), Water::id(), Steam::id(), Sand::id()]),
+                StateQuery::any([Air::id(), Water::id(), Steam::id(), Sand::id()]),
             ),
         )
     }
 }
 
+/// A `chance` to set a flammable neighbor alight as an [`Ember`].
+pub(super) fn ignite(chance: f32) -> impl Step {
+    Chance {
+        to: Infect {
+            directions: Directions::ALL,
+            open: StateQuery::tagged(Tags::FLAMMABLE),
+            into: [Ember::id()].into(),
+        },
+        chance,
+    }
+}
+
 #[derive(UniqueTypeId)]
 #[UniqueTypeIdType = "u8"]
 pub struct Ember;
@@ -81,42 +84,95 @@ impl StateInfo for Ember {
         },
     };
     const HIDDEN: bool = true;
+    const SYMBOL: char = 'E';
+    const DENSITY: f32 = 0.5;
+    const TAGS: Tags = Tags::HOT.union(Tags::IGNITES);
 }
 
 impl Behavior for Ember {
     fn tick(&self) -> impl Step {
         (
+            // Burn out
             Chance {
-                to: Set([Air::id()]),
+                to: Set([Ash::id()].into()),
                 chance: 0.05,
             },
-            Chance {
-                to: Infect {
-                    directions: EdgeDirection::ALL_DIRECTIONS,
-                    open: Any([
-                        Seed::id(),
-                        Sapling::id(),
-                        Trunk::id(),
-                        DeadTrunk::id(),
-                        BranchLeft::id(),
-                        BranchRight::id(),
-                        Twig::id(),
-                        Leaf::id(),
-                    ]),
-                    into: [Self::id()],
-                },
-                chance: 0.5,
+            Blow {
+                open: StateQuery::any([Air::id()]),
             },
+            ignite(0.5),
             Infect {
-                directions: EdgeDirection::ALL_DIRECTIONS,
-                open: Any([Air::id()]),
-                into: [Fire::id()],
+                directions: Directions::ALL,
+                open: StateQuery::any([Air::id()]),
+                into: [Fire::id()].into(),
             },
             Annihilate {
-                directions: EdgeDirection::ALL_DIRECTIONS,
-                open: Any([Water::id()]),
-                into: [Steam::id()],
+                directions: Directions::ALL,
+                open: StateQuery::any([Water::id()]),
+                into: [Steam::id()].into(),
             },
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::golden::{assert_golden, count};
+
+    #[test]
+    fn water_makes_steam() {
+        let states = assert_golden(
+            "fire_water_makes_steam",
+            0,
+            4,
+            r"
+                   . . . .
+                  . . . . .
+                 . . . . . .
+                W W W W W W W
+                 F F F F F F
+                  # # # # #
+                   # # # #
+            ",
+        );
+        assert!(count(&states, Steam::id()) > 0);
+    }
+
+    #[test]
+    fn burns_trees() {
+        let states = assert_golden(
+            "fire_burns_trees",
+            4,
+            15,
+            r"
+                   . . . .
+                  . * * * .
+                 . * T T * .
+                . . . T . . .
+                 . . . T F .
+                  # # # # #
+                   # # # #
+            ",
+        );
+        assert_eq!(count(&states, Trunk::id()), 0);
+    }
+
+    #[test]
+    fn ember_spreads() {
+        assert_golden(
+            "ember_spreads",
+            5,
+            10,
+            r"
+                   . . . .
+                  . . . . .
+                 . D D D D .
+                . D D E D D .
+                 . D D D D .
+                  # # # # #
+                   # # # #
+            ",
+        );
+    }
+}
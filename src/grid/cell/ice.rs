@@ -0,0 +1,159 @@
+use bevy::prelude::*;
+
+use super::*;
+use crate::behavior::*;
+
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Ice;
+
+impl StateInfo for Ice {
+    const NAME: &'static str = "Ice";
+    const COLOR: HexColor = HexColor::Static(Color::Rgba {
+        red: 0.7,
+        green: 0.85,
+        blue: 1.0,
+        alpha: 0.8,
+    });
+    const HIDDEN: bool = false;
+    const SYMBOL: char = 'I';
+    const DENSITY: f32 = 0.92;
+    const ACID_RESISTANCE: f32 = 0.6;
+}
+
+impl Behavior for Ice {
+    fn tick(&self) -> impl Step {
+        Near::any_adjacent(
+            StateQuery::tagged(Tags::HOT),
+            Chance {
+                to: Set([Water::id()].into()),
+                chance: 0.2,
+            },
+        )
+    }
+}
+
+/// A light powder that piles up, and is pressed into [`Ice`] under
+/// enough of itself.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Snow;
+
+impl StateInfo for Snow {
+    const NAME: &'static str = "Snow";
+    const COLOR: HexColor = HexColor::Noise {
+        base_color: Color::rgb(0.92, 0.95, 1.0),
+        offset_color: Color::Rgba {
+            red: 0.05,
+            green: 0.05,
+            blue: 0.0,
+            alpha: 0.0,
+        },
+        speed: Vec2::ZERO,
+        scale: Vec2::ONE,
+    };
+    const HIDDEN: bool = false;
+    const SYMBOL: char = 's';
+    const DENSITY: f32 = 0.3;
+    const PHASE: Phase = Phase::Powder;
+    const ACID_RESISTANCE: f32 = 0.3;
+}
+
+impl Behavior for Snow {
+    fn tick(&self) -> impl Step {
+        (
+            Near::any_adjacent(
+                StateQuery::tagged(Tags::HOT),
+                Chance {
+                    to: Set([Water::id()].into()),
+                    chance: 0.4,
+                },
+            ),
+            Buried {
+                depth: 4,
+                by: StateQuery::any([Snow::id(), Ice::id()]),
+                then: Chance {
+                    to: Set([Ice::id()].into()),
+                    chance: 0.05,
+                },
+            },
+            Blow {
+                open: StateQuery::any([Air::id()]),
+            },
+            Fall {
+                acceleration: 0.25,
+                terminal: 1.5,
+                splash: 0.0,
+                transfer: 0.0,
+            },
+            Displace::sink([Heading::Down, Heading::DownLeft, Heading::DownRight]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::golden::{assert_golden, count};
+
+    #[test]
+    fn freezes_water() {
+        let states = assert_golden(
+            "ice_freezes_water",
+            0,
+            60,
+            r"
+                   . . . .
+                  . . . . .
+                 # . . . . #
+                # W W W W W #
+                 # W W W W #
+                  # I I I #
+                   # # # #
+            ",
+        );
+        assert!(count(&states, Ice::id()) > 3);
+    }
+
+    #[test]
+    fn melts_by_embers() {
+        assert_golden(
+            "ice_melts_by_embers",
+            1,
+            10,
+            r"
+                   . . . .
+                  . . . . .
+                 . . . . . .
+                . I I E I I .
+                 . I I I I .
+                  # # # # #
+                   # # # #
+            ",
+        );
+    }
+
+    #[test]
+    fn snow_compacts_into_ice() {
+        assert_golden(
+            "snow_compacts_into_ice",
+            2,
+            60,
+            r"
+                      s s s s s s s
+                     s s s s s s s s
+                    s s s s s s s s s
+                   s s s s s s s s s s
+                  s s s s s s s s s s s
+                 s s s s s s s s s s s s
+                s s s s s s s s s s s s s
+                 s s s s s s s s s s s s
+                  s s s s s s s s s s s
+                   s s s s s s s s s s
+                    s s s s s s s s s
+                     s s s s s s s s
+                      # # # # # # #
+            ",
+        );
+    }
+}
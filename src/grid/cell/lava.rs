@@ -0,0 +1,196 @@
+use bevy::prelude::*;
+
+use super::*;
+use crate::behavior::*;
+
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Lava;
+
+impl StateInfo for Lava {
+    const NAME: &'static str = "Lava";
+    const COLOR: HexColor = HexColor::Noise {
+        base_color: Color::rgb(0.9, 0.25, 0.0),
+        offset_color: Color::Rgba {
+            red: 0.1,
+            green: 0.3,
+            blue: 0.0,
+            alpha: 0.0,
+        },
+        speed: Vec2::splat(0.2),
+        scale: Vec2::splat(0.3),
+    };
+    const HIDDEN: bool = false;
+    const SYMBOL: char = 'L';
+    const DENSITY: f32 = 2.7;
+    const PHASE: Phase = Phase::Liquid;
+    const TAGS: Tags = Tags::HOT.union(Tags::IGNITES);
+}
+
+impl Behavior for Lava {
+    fn tick(&self) -> impl Step {
+        (
+            // Quench, into glassy obsidian when surrounded by water
+            MaybeNear::some_adjacent(
+                StateQuery::any([Water::id()]),
+                3,
+                quench(Obsidian::id()),
+                Near::any_adjacent(StateQuery::any([Water::id()]), quench(Stone::id())),
+            ),
+            ignite(0.5),
+            // Slowly melt sand into glass
+            Chance {
+                to: Infect {
+                    directions: Directions::ALL,
+                    open: StateQuery::any([Sand::id()]),
+                    into: [Glass::id()].into(),
+                },
+                chance: 0.05,
+            },
+            Fall {
+                acceleration: 0.25,
+                terminal: 2.0,
+                splash: 0.0,
+                transfer: 0.0,
+            },
+            Displace::sink([Heading::Down, Heading::DownLeft, Heading::DownRight]),
+            // Thick, so it only flows sideways now and then
+            Chance {
+                to: RandomSwap::adjacent(
+                    [Heading::Left, Heading::Right],
+                    StateQuery::any([Air::id()]),
+                ),
+                chance: 0.2,
+            },
+        )
+    }
+}
+
+/// Cool into `into`, boiling a neighboring [`Water`] into [`Steam`].
+fn quench(into: StateId) -> impl Step {
+    All((
+        Set(into.into()),
+        Infect {
+            directions: Directions::ALL,
+            open: StateQuery::any([Water::id()]),
+            into: [Steam::id()].into(),
+        },
+    ))
+}
+
+/// Volcanic glass, from [`Lava`] cooled quickly in [`Water`].
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Obsidian;
+
+impl StateInfo for Obsidian {
+    const NAME: &'static str = "Obsidian";
+    const COLOR: HexColor = HexColor::Static(Color::rgb(0.12, 0.06, 0.18));
+    const HIDDEN: bool = false;
+    const SYMBOL: char = 'O';
+    const DENSITY: f32 = 2.4;
+    const ACID_RESISTANCE: f32 = 0.95;
+}
+
+impl Behavior for Obsidian {}
+
+/// [`Sand`] melted by [`Lava`].
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Glass;
+
+impl StateInfo for Glass {
+    const NAME: &'static str = "Glass";
+    const COLOR: HexColor = HexColor::Static(Color::Rgba {
+        red: 0.75,
+        green: 0.9,
+        blue: 0.95,
+        alpha: 0.5,
+    });
+    const HIDDEN: bool = false;
+    const SYMBOL: char = 'G';
+    const DENSITY: f32 = 2.5;
+}
+
+impl Behavior for Glass {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::golden::{assert_golden, count};
+
+    #[test]
+    fn flows() {
+        assert_golden(
+            "lava_flows",
+            0,
+            30,
+            r"
+                   . L L .
+                  . L L L .
+                 . . . . . .
+                . . . . . . .
+                 . . . . . .
+                  . . . . .
+                   # # # #
+            ",
+        );
+    }
+
+    #[test]
+    fn cools_in_water() {
+        let states = assert_golden(
+            "lava_cools_in_water",
+            1,
+            20,
+            r"
+                   . L L .
+                  . L L L .
+                 # . . . . #
+                # W W W W W #
+                 # W W W W #
+                  # W W W #
+                   # # # #
+            ",
+        );
+        assert_eq!(count(&states, Lava::id()), 0);
+        assert!(count(&states, Obsidian::id()) > 0);
+    }
+
+    #[test]
+    fn melts_sand_into_glass() {
+        let states = assert_golden(
+            "lava_melts_sand_into_glass",
+            2,
+            40,
+            r"
+                   . . . .
+                  . L L L .
+                 . L L L L .
+                . S S S S S .
+                 # S S S S #
+                  # S S S #
+                   # # # #
+            ",
+        );
+        assert!(count(&states, Glass::id()) > 0);
+    }
+
+    #[test]
+    fn burns_trees() {
+        assert_golden(
+            "lava_burns_trees",
+            3,
+            10,
+            r"
+                   . . . .
+                  . . L . .
+                 . * * * * .
+                . * * T * * .
+                 . . . T . .
+                  # # # # #
+                   # # # #
+            ",
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+
+use super::*;
+use crate::behavior::*;
+
+/// Conway's game of life adapted to hexes, with the `B2/S34` rule.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Life;
+
+impl Life {
+    pub const RULE: LifeRule = LifeRule {
+        birth: NeighborCounts::new(&[2]),
+        survival: NeighborCounts::new(&[3, 4]),
+    };
+}
+
+impl StateInfo for Life {
+    const NAME: &'static str = "Life";
+    const COLOR: HexColor = HexColor::Static(Color::rgb(0.3, 1.0, 0.6));
+    const HIDDEN: bool = false;
+    const SYMBOL: char = 'l';
+    const UPDATE: UpdateMode = UpdateMode::Synchronous;
+}
+
+impl Behavior for Life {
+    fn tick(&self) -> impl Step {
+        Automaton {
+            rule: Self::RULE,
+            dead: Air::id(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_turborand::{DelegatedRng, GlobalRng};
+    use hexx::Hex;
+
+    use super::*;
+    use crate::grid::golden::{self, assert_golden};
+
+    #[test]
+    fn cluster_evolves() {
+        assert_golden(
+            "life_cluster_evolves",
+            0,
+            4,
+            r"
+                   . . . .
+                  . . . . .
+                 . . l l . .
+                . . l . l . .
+                 . . l l . .
+                  . . . . .
+                   . . . .
+            ",
+        );
+    }
+
+    /// Every cell is updated from the board as it was at the start of
+    /// the tick, whatever order they are ticked in.
+    #[test]
+    fn updates_all_at_once() {
+        let registry = CellRegistry::builtin();
+        let mut rng = GlobalRng::with_seed(7);
+        let mut states = BoardState::new(6, &registry);
+        for hex in states.positions.clone() {
+            if rng.bool() {
+                states.set_next(hex, Life::id());
+            }
+        }
+        states.commit();
+
+        let alive = |states: &BoardState, hex: Hex| states.get_current(hex) == Some(&Life::id());
+        let expected = states
+            .positions
+            .iter()
+            .map(|hex| {
+                let count = hex
+                    .all_neighbors()
+                    .into_iter()
+                    .filter(|neighbor| alive(&states, *neighbor))
+                    .count();
+                if alive(&states, *hex) {
+                    Life::RULE.survival.contains(count)
+                } else {
+                    Life::RULE.birth.contains(count)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        golden::tick(&mut states, &registry, &mut rng);
+        let actual = states
+            .positions
+            .iter()
+            .map(|hex| alive(&states, *hex))
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+    }
+}
@@ -1,9 +1,32 @@
+mod acid;
+pub use acid::{Acid, DilutedAcid, ToxicGas};
 mod air;
 pub use air::Air;
+mod ash;
+pub use ash::Ash;
+mod creature;
+pub use creature::{Agent, Ant, Bird, Fish};
+mod electric;
+pub use electric::{Battery, HotMetal, Metal, Spark};
+mod emitter;
+pub use emitter::{Cloner, Drain, Emitter, Spout};
+mod explosive;
+pub use explosive::{Gunpowder, Tnt};
 mod fire;
+use fire::ignite;
 pub use fire::{Ember, Fire};
+mod ice;
+pub use ice::{Ice, Snow};
+mod lava;
+pub use lava::{Glass, Lava, Obsidian};
+mod life;
+pub use life::Life;
+mod oil;
+pub use oil::{BurningOil, Oil};
 mod sand;
 pub use sand::Sand;
+mod smoke;
+pub use smoke::{Smoke, ThickSmoke};
 mod steam;
 pub use steam::Steam;
 mod stone;
@@ -19,6 +42,7 @@ pub use wind::Wind;
 
 use crate::behavior::{Noop, StateId, Step};
 use crate::grid::BoardState;
+use crate::rng::CellRng;
 
 use bevy::prelude::*;
 use bevy::utils::HashMap;
@@ -31,25 +55,7 @@ pub struct Plugin;
 
 impl bevy::prelude::Plugin for Plugin {
     fn build(&self, app: &mut App) {
-        let mut registry = CellRegistry::default();
-        registry.add(Air);
-        registry.add(Ember);
-        registry.add(Fire);
-        registry.add(Sand);
-        registry.add(Steam);
-        registry.add(Stone);
-        registry.add(BranchLeft);
-        registry.add(BranchRight);
-        registry.add(DeadTrunk);
-        registry.add(Leaf);
-        registry.add(Sapling);
-        registry.add(Seed);
-        registry.add(Trunk);
-        registry.add(Twig);
-        registry.add(Void);
-        registry.add(Water);
-        registry.add(Wind);
-        app.insert_resource(registry);
+        app.insert_resource(CellRegistry::builtin());
     }
 }
 
@@ -66,6 +72,101 @@ pub enum HexColor {
         speed: Vec2,
         scale: Vec2,
     },
+    /// Fades from `color` to clear as the cell ages over `lifetime`
+    /// ticks, going by the age kept in its [`CellData::Age`].
+    Fading {
+        color: Color,
+        lifetime: u16,
+    },
+}
+
+/// When the cells of a state are updated in a tick.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateMode {
+    /// One at a time in a random order, each seeing the changes made
+    /// by the cells before it.
+    #[default]
+    Asynchronous,
+    /// All at once, before any asynchronous cells, each seeing the
+    /// board as it was at the start of the tick.
+    Synchronous,
+}
+
+/// How a state moves through others.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// Never moved by other states.
+    #[default]
+    Solid,
+    Powder,
+    Liquid,
+    Gas,
+}
+
+impl Phase {
+    /// Whether other states can pass through this one.
+    pub fn is_fluid(&self) -> bool {
+        matches!(self, Phase::Liquid | Phase::Gas)
+    }
+
+    /// The [`Tags`] every state in this phase has.
+    pub const fn tags(&self) -> Tags {
+        match self {
+            Phase::Liquid => Tags::LIQUID,
+            Phase::Gas => Tags::GAS,
+            Phase::Solid | Phase::Powder => Tags::NONE,
+        }
+    }
+}
+
+/// Categories a state belongs to, so rules can match every state in
+/// a category with [`StateQuery::Tagged`] instead of listing them.
+///
+/// [`StateQuery::Tagged`]: crate::behavior::StateQuery::Tagged
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Tags(u8);
+
+impl Tags {
+    pub const NONE: Self = Self(0);
+    /// Burns when touched by [`Fire`] or an [`Ember`].
+    pub const FLAMMABLE: Self = Self(1 << 0);
+    pub const LIQUID: Self = Self(1 << 1);
+    /// Part of a plant.
+    pub const ORGANIC: Self = Self(1 << 2);
+    pub const GAS: Self = Self(1 << 3);
+    /// Hot enough to melt [`Ice`] and [`Snow`].
+    pub const HOT: Self = Self(1 << 4);
+    /// Sets [`Oil`] alight.
+    pub const IGNITES: Self = Self(1 << 5);
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Whether every tag in `other` is also in `self`.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl std::ops::BitOr for Tags {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+/// Physical properties of a state that [`Step`]s can look up from the
+/// [`BoardState`] without knowing which state they're dealing with.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Properties {
+    /// Relative to [`Water`], which has a density of `1.0`.
+    pub density: f32,
+    pub phase: Phase,
+    pub tags: Tags,
+    /// See [`StateInfo::ACID_RESISTANCE`].
+    pub acid_resistance: f32,
 }
 
 pub struct CellEntry {
@@ -73,6 +174,12 @@ pub struct CellEntry {
     pub name: Cow<'static, str>,
     pub color: HexColor,
     pub hidden: bool,
+    /// Only read when drawing boards as text in tests.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub symbol: char,
+    pub conserved: bool,
+    pub update: UpdateMode,
+    pub properties: Properties,
 }
 
 #[derive(Resource, Default, Deref)]
@@ -81,6 +188,55 @@ pub struct CellRegistry {
 }
 
 impl CellRegistry {
+    /// A registry containing every cell type that ships with the sim.
+    pub fn builtin() -> Self {
+        let mut registry = CellRegistry::default();
+        registry.add(Acid);
+        registry.add(Air);
+        registry.add(Ant);
+        registry.add(Ash);
+        registry.add(Battery);
+        registry.add(Bird);
+        registry.add(BurningOil);
+        registry.add(Cloner);
+        registry.add(DilutedAcid);
+        registry.add(Drain);
+        registry.add(Ember);
+        registry.add(Fire);
+        registry.add(Fish);
+        registry.add(Glass);
+        registry.add(Gunpowder);
+        registry.add(HotMetal);
+        registry.add(Ice);
+        registry.add(Lava);
+        registry.add(Life);
+        registry.add(Metal);
+        registry.add(Obsidian);
+        registry.add(Oil);
+        registry.add(Sand);
+        registry.add(Smoke);
+        registry.add(Snow);
+        registry.add(Spark);
+        registry.add(Spout);
+        registry.add(Steam);
+        registry.add(Stone);
+        registry.add(ThickSmoke);
+        registry.add(Tnt);
+        registry.add(ToxicGas);
+        registry.add(BranchLeft);
+        registry.add(BranchRight);
+        registry.add(DeadTrunk);
+        registry.add(Leaf);
+        registry.add(Sapling);
+        registry.add(Seed);
+        registry.add(Trunk);
+        registry.add(Twig);
+        registry.add(Void);
+        registry.add(Water);
+        registry.add(Wind);
+        registry
+    }
+
     pub fn add<T>(&mut self, tickable: T)
     where
         T: StateInfo + Behavior + Send + Sync + 'static,
@@ -96,6 +252,15 @@ impl CellRegistry {
                 name: T::NAME.into(),
                 color: T::COLOR,
                 hidden: T::HIDDEN,
+                symbol: T::SYMBOL,
+                conserved: T::CONSERVED,
+                update: T::UPDATE,
+                properties: Properties {
+                    density: T::DENSITY,
+                    phase: T::PHASE,
+                    tags: T::TAGS.union(T::PHASE.tags()),
+                    acid_resistance: T::ACID_RESISTANCE,
+                },
             },
         );
     }
@@ -107,6 +272,15 @@ impl CellRegistry {
             .map(|(id, entry)| (*id, entry.name.to_string()))
     }
 
+    /// The [`Properties`] of every state, indexed by [`StateId`].
+    pub fn properties(&self) -> Vec<Properties> {
+        let mut properties = vec![Properties::default(); u8::MAX as usize + 1];
+        for (id, entry) in &self.inner {
+            properties[id.0 as usize] = entry.properties;
+        }
+        properties
+    }
+
     pub fn color(&self, id: &StateId) -> &HexColor {
         self.inner
             .get(id)
@@ -115,15 +289,98 @@ impl CellRegistry {
     }
 }
 
-#[derive(Debug, Deref, DerefMut)]
-pub struct BoardSlice(pub Vec<(Hex, StateId)>);
+/// Extra state a cell keeps from tick to tick, besides its [`StateId`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CellData {
+    Emitter(Emitter),
+    Agent(Agent),
+    /// Ticks since the cell came into being.
+    Age(u16),
+}
+
+impl From<Emitter> for CellData {
+    fn from(emitter: Emitter) -> Self {
+        Self::Emitter(emitter)
+    }
+}
+
+impl From<Agent> for CellData {
+    fn from(agent: Agent) -> Self {
+        Self::Agent(agent)
+    }
+}
+
+/// The cells a [`Step`] writes, applied all at once or not at all.
+#[derive(Debug, Default, Deref, DerefMut)]
+pub struct BoardSlice {
+    #[deref]
+    pub cells: Vec<(Hex, StateId)>,
+    /// The velocity of cells written in [`Self::cells`]. Any cell
+    /// without one comes to rest.
+    pub velocities: Vec<(Hex, Vec2)>,
+    /// The [`CellData`] of cells written in [`Self::cells`]. Any cell
+    /// without any forgets what it had.
+    pub data: Vec<(Hex, CellData)>,
+}
 
 impl BoardSlice {
-    pub const EMPTY: Self = Self(Vec::new());
+    pub const EMPTY: Self = Self {
+        cells: Vec::new(),
+        velocities: Vec::new(),
+        data: Vec::new(),
+    };
+
+    pub fn new(cells: Vec<(Hex, StateId)>) -> Self {
+        Self {
+            cells,
+            velocities: Vec::new(),
+            data: Vec::new(),
+        }
+    }
+
+    /// Give the cell written at `hex` a velocity.
+    pub fn with_velocity(mut self, hex: Hex, velocity: Vec2) -> Self {
+        self.velocities.push((hex, velocity));
+        self
+    }
+
+    /// Give the cell written at `hex` some [`CellData`].
+    pub fn with_data(mut self, hex: Hex, data: impl Into<CellData>) -> Self {
+        self.data.push((hex, data.into()));
+        self
+    }
+
+    /// Combine two slices, or `None` if they both write to a hex.
+    pub fn merge(mut self, other: BoardSlice) -> Option<Self> {
+        if other
+            .iter()
+            .any(|(hex, _id)| self.iter().any(|(other, _id)| hex == other))
+        {
+            None
+        } else {
+            self.cells.extend(other.cells);
+            self.velocities.extend(other.velocities);
+            self.data.extend(other.data);
+            Some(self)
+        }
+    }
+
+    /// Combine two slices. Where both write to a hex, `other` wins,
+    /// along with its velocity and data for that hex.
+    pub fn overlay(mut self, other: BoardSlice) -> Self {
+        let written = |hex: &Hex| other.iter().any(|(other, _id)| hex == other);
+        self.cells.retain(|(hex, _id)| !written(hex));
+        self.velocities.retain(|(hex, _velocity)| !written(hex));
+        self.data.retain(|(hex, _data)| !written(hex));
+        self.cells.extend(other.cells);
+        self.velocities.extend(other.velocities);
+        self.data.extend(other.data);
+        self
+    }
 }
 
 pub trait Tick {
-    fn tick(&self, _hex: Hex, _states: &BoardState, _rng: f32) -> Option<BoardSlice>;
+    fn tick(&self, _hex: Hex, _states: &BoardState, _rng: &mut CellRng) -> Option<BoardSlice>;
 }
 
 pub trait Behavior {
@@ -136,7 +393,7 @@ impl<T> Tick for T
 where
     T: Behavior,
 {
-    fn tick(&self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
+    fn tick(&self, hex: Hex, states: &BoardState, rng: &mut CellRng) -> Option<BoardSlice> {
         self.tick().apply(hex, states, rng)
     }
 }
@@ -147,4 +404,19 @@ pub trait StateInfo: UniqueTypeId<u8> {
     const NAME: &'static str = "Unknown";
     const COLOR: HexColor = HexColor::Invisible;
     const HIDDEN: bool = true;
+    /// Character used to draw this state in a text board.
+    const SYMBOL: char = '?';
+    /// Whether this state can only be moved around the board while
+    /// [`Conservation`](crate::grid::Conservation) is enabled.
+    const CONSERVED: bool = false;
+    /// Relative to [`Water`], which has a density of `1.0`.
+    const DENSITY: f32 = 1.0;
+    const PHASE: Phase = Phase::Solid;
+    /// [`Tags::LIQUID`] and [`Tags::GAS`] are added from
+    /// [`Self::PHASE`] and don't need to be listed.
+    const TAGS: Tags = Tags::NONE;
+    /// How well this state stands up to [`Acid`], from `0.0`, which
+    /// dissolves on contact, to `1.0`, which never dissolves.
+    const ACID_RESISTANCE: f32 = 1.0;
+    const UPDATE: UpdateMode = UpdateMode::Asynchronous;
 }
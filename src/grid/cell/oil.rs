@@ -0,0 +1,176 @@
+use bevy::prelude::*;
+
+use super::*;
+use crate::behavior::*;
+
+/// How a pool of oil moves, whether it's burning or not.
+fn flow() -> impl Step {
+    (
+        Fall {
+            acceleration: 0.5,
+            terminal: 3.0,
+            splash: 0.25,
+            transfer: 0.25,
+        },
+        Displace::sink([Heading::Down, Heading::DownLeft, Heading::DownRight]),
+        Choose {
+            a: Displace::sink([Heading::Left, Heading::Right]),
+            b: RandomSwap::adjacent(
+                [Heading::Left, Heading::Right],
+                StateQuery::any([Oil::id(), BurningOil::id()]),
+            ),
+            chance: 0.9,
+        },
+    )
+}
+
+/// A liquid lighter than [`Water`] that floats on it and burns for a
+/// long time once lit.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Oil;
+
+impl StateInfo for Oil {
+    const NAME: &'static str = "Oil";
+    const COLOR: HexColor = HexColor::Noise {
+        base_color: Color::rgb(0.25, 0.18, 0.05),
+        offset_color: Color::Rgba {
+            red: 0.05,
+            green: 0.05,
+            blue: 0.0,
+            alpha: 0.0,
+        },
+        speed: Vec2::X,
+        scale: Vec2::splat(0.2),
+    };
+    const HIDDEN: bool = false;
+    const SYMBOL: char = 'o';
+    const CONSERVED: bool = true;
+    const DENSITY: f32 = 0.85;
+    const PHASE: Phase = Phase::Liquid;
+}
+
+impl Behavior for Oil {
+    fn tick(&self) -> impl Step {
+        (
+            Near::any_adjacent(
+                StateQuery::tagged(Tags::IGNITES),
+                Chance {
+                    to: Set([BurningOil::id()].into()),
+                    chance: 0.5,
+                },
+            ),
+            flow(),
+        )
+    }
+}
+
+/// [`Oil`] on fire, which keeps flowing, so fire spreads across the
+/// surface of any water it floats on.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct BurningOil;
+
+impl StateInfo for BurningOil {
+    const NAME: &'static str = "Burning Oil";
+    const COLOR: HexColor = HexColor::Flickering {
+        base_color: Color::rgb(0.35, 0.15, 0.0),
+        offset_color: Color::ORANGE,
+    };
+    const HIDDEN: bool = true;
+    const SYMBOL: char = 'b';
+    const DENSITY: f32 = 0.85;
+    const PHASE: Phase = Phase::Liquid;
+    const TAGS: Tags = Tags::HOT.union(Tags::IGNITES);
+}
+
+impl Behavior for BurningOil {
+    fn tick(&self) -> impl Step {
+        (
+            Chance {
+                to: Set([ThickSmoke::id()].into()),
+                chance: 0.01,
+            },
+            ignite(0.5),
+            Chance {
+                to: Infect {
+                    directions: [Heading::Up, Heading::UpLeft, Heading::UpRight].into(),
+                    open: StateQuery::any([Air::id()]),
+                    into: [Fire::id()].into(),
+                },
+                chance: 0.3,
+            },
+            flow(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::golden::assert_golden;
+
+    #[test]
+    fn floats_on_water() {
+        let states = assert_golden(
+            "oil_floats_on_water",
+            0,
+            60,
+            r"
+                   . . . .
+                  # o o o #
+                 # o o o o #
+                # W W W W W #
+                 # W W W W #
+                  # W W W #
+                   # # # #
+            ",
+        );
+        let rows = |of: StateId| {
+            states
+                .iter()
+                .filter(move |(_hex, id)| **id == of)
+                .map(|(hex, _id)| hex.y)
+        };
+        // Every row of oil is above every row of water
+        assert!(rows(Oil::id()).max() < rows(Water::id()).min());
+    }
+
+    #[test]
+    fn separates_from_water() {
+        assert_golden(
+            "oil_separates_from_water",
+            1,
+            80,
+            r"
+                    . . . . .
+                   # W W W W #
+                  # W W W W W #
+                 # o o o o o o #
+                # o o o o o o o #
+                 # W W W W W W #
+                  # o o o o o #
+                   # # # # # #
+                    # # # # #
+            ",
+        );
+    }
+
+    #[test]
+    fn burns_across_water() {
+        assert_golden(
+            "oil_burns_across_water",
+            2,
+            10,
+            r"
+                   . . . .
+                  . . . . .
+                 # . . . . #
+                # F o o o o #
+                 # W W W W #
+                  # W W W #
+                   # # # #
+            ",
+        );
+    }
+}
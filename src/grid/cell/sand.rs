@@ -1,8 +1,7 @@
 use bevy::prelude::*;
-use hexx::EdgeDirection;
 
 use super::*;
-use crate::behavior::{StateQuery::*, *};
+use crate::behavior::*;
 
 #[derive(UniqueTypeId)]
 #[UniqueTypeIdType = "u8"]
@@ -22,16 +21,87 @@ impl StateInfo for Sand {
         scale: Vec2::ONE,
     };
     const HIDDEN: bool = false;
+    const SYMBOL: char = 'S';
+    const CONSERVED: bool = true;
+    const DENSITY: f32 = 1.6;
+    const PHASE: Phase = Phase::Powder;
+    const ACID_RESISTANCE: f32 = 0.2;
 }
 
 impl Behavior for Sand {
     fn tick(&self) -> impl Step {
-        RandomSwap::adjacent(
-            [
-                EdgeDirection::POINTY_BOTTOM_LEFT,
-                EdgeDirection::POINTY_BOTTOM_RIGHT,
-            ],
-            Any([Air::id(), Sand::id(), Steam::id()]),
+        (
+            Blow {
+                open: StateQuery::any([Air::id()]),
+            },
+            Fall {
+                acceleration: 0.5,
+                terminal: 4.0,
+                splash: 0.0,
+                transfer: 0.5,
+            },
+            Displace::sink([Heading::Down, Heading::DownLeft, Heading::DownRight]),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::golden::assert_golden;
+
+    #[test]
+    fn pile() {
+        assert_golden(
+            "sand_pile",
+            0,
+            40,
+            r"
+                    . . S . .
+                   . . S S . .
+                  . . . S . . .
+                 . . . . . . . .
+                . . . . . . . . .
+                 . . . . . . . .
+                  . . . . . . .
+                   # # # # # #
+                    # # # # #
+            ",
+        );
+    }
+
+    #[test]
+    fn sinks_through_steam() {
+        assert_golden(
+            "sand_sinks_through_steam",
+            1,
+            10,
+            r"
+                   . . S S
+                  . . S S .
+                 . . ~ ~ . .
+                . . ~ ~ ~ . .
+                 . ~ ~ ~ ~ .
+                  # ~ ~ ~ #
+                   # # # #
+            ",
+        );
+    }
+
+    #[test]
+    fn mixes_with_water() {
+        assert_golden(
+            "sand_mixes_with_water",
+            2,
+            10,
+            r"
+                   . S S .
+                  . S S S .
+                 # . . . . #
+                # W W W W W #
+                 # W W W W #
+                  # W W W #
+                   # # # #
+            ",
+        );
+    }
+}
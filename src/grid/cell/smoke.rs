@@ -0,0 +1,142 @@
+use bevy::prelude::*;
+
+use super::*;
+use crate::behavior::*;
+
+/// Rises and drifts, fading away over its lifetime until it clears
+/// into [`Air`].
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct Smoke;
+
+impl Smoke {
+    /// Ticks until it clears.
+    pub const LIFETIME: u16 = 50;
+}
+
+impl StateInfo for Smoke {
+    const NAME: &'static str = "Smoke";
+    const COLOR: HexColor = HexColor::Fading {
+        color: Color::Rgba {
+            red: 0.3,
+            green: 0.3,
+            blue: 0.3,
+            alpha: 0.5,
+        },
+        lifetime: Self::LIFETIME,
+    };
+    const HIDDEN: bool = false;
+    const SYMBOL: char = '&';
+    const DENSITY: f32 = 0.0008;
+    const PHASE: Phase = Phase::Gas;
+}
+
+impl Behavior for Smoke {
+    fn tick(&self) -> impl Step {
+        Age {
+            lifetime: Self::LIFETIME,
+            into: Air::id().into(),
+            then: drift(),
+        }
+    }
+}
+
+/// How smoke moves, pushed by the wind as it rises.
+fn drift() -> impl Step {
+    (
+        Blow {
+            open: StateQuery::any([Air::id()]),
+        },
+        Displace::rise([Heading::Up, Heading::UpLeft, Heading::UpRight]),
+        RandomSwap::adjacent(
+            [Heading::Left, Heading::Right],
+            StateQuery::any([Air::id()]),
+        ),
+    )
+}
+
+/// Dark smoke from burning [`Oil`], which thins out into [`Smoke`]
+/// once it has lived for as long.
+#[derive(UniqueTypeId)]
+#[UniqueTypeIdType = "u8"]
+pub struct ThickSmoke;
+
+impl StateInfo for ThickSmoke {
+    const NAME: &'static str = "Thick Smoke";
+    const COLOR: HexColor = HexColor::Static(Color::Rgba {
+        red: 0.1,
+        green: 0.1,
+        blue: 0.1,
+        alpha: 0.85,
+    });
+    const HIDDEN: bool = false;
+    const SYMBOL: char = '@';
+    const DENSITY: f32 = 0.0009;
+    const PHASE: Phase = Phase::Gas;
+}
+
+impl Behavior for ThickSmoke {
+    fn tick(&self) -> impl Step {
+        Age {
+            lifetime: Smoke::LIFETIME,
+            into: Smoke::id().into(),
+            then: drift(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::golden::{self, assert_golden, count};
+
+    const PUFF: &str = r"
+                   . . . .
+                  . . . . .
+                 . . . . . .
+                . . . . . . .
+                 . . . . . .
+                  . & & & .
+                   # # # #
+            ";
+
+    #[test]
+    fn rises() {
+        let states = assert_golden("smoke_rises", 0, 20, PUFF);
+        assert_eq!(count(&states, Smoke::id()), 3);
+    }
+
+    #[test]
+    fn clears_at_the_end_of_its_lifetime() {
+        let lifetime = Smoke::LIFETIME as usize;
+        let states = golden::run(0, lifetime - 1, PUFF, |_states| {});
+        let ages = states
+            .iter()
+            .filter(|(_hex, id)| **id == Smoke::id())
+            .map(|(hex, _id)| states.age(hex))
+            .collect::<Vec<_>>();
+        assert_eq!(ages, vec![Some(Smoke::LIFETIME - 1); 3]);
+        let states = golden::run(0, lifetime, PUFF, |_states| {});
+        assert_eq!(count(&states, Smoke::id()), 0);
+    }
+
+    #[test]
+    fn thick_smoke_thins_out() {
+        let states = assert_golden(
+            "thick_smoke_thins_out",
+            1,
+            60,
+            r"
+                   . . . .
+                  . . . . .
+                 . . . . . .
+                . . . . . . .
+                 . . . . . .
+                  . @ @ @ .
+                   # # # #
+            ",
+        );
+        assert_eq!(count(&states, ThickSmoke::id()), 0);
+        assert_eq!(count(&states, Smoke::id()), 3);
+    }
+}
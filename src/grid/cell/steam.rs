@@ -1,8 +1,7 @@
 use bevy::prelude::*;
-use hexx::EdgeDirection;
 
 use super::*;
-use crate::behavior::{StateQuery::*, *};
+use crate::behavior::*;
 
 #[derive(UniqueTypeId)]
 #[UniqueTypeIdType = "u8"]
@@ -17,33 +16,81 @@ impl StateInfo for Steam {
         alpha: 0.01,
     });
     const HIDDEN: bool = false;
+    const SYMBOL: char = '~';
+    const DENSITY: f32 = 0.0006;
+    const PHASE: Phase = Phase::Gas;
+    const TAGS: Tags = Tags::HOT;
 }
 
 impl Behavior for Steam {
     fn tick(&self) -> impl Step {
         (
+            Blow {
+                open: StateQuery::any([Air::id()]),
+            },
             // Move up
-            RandomSwap::adjacent(
-                [
-                    EdgeDirection::POINTY_TOP_LEFT,
-                    EdgeDirection::POINTY_TOP_RIGHT,
-                ],
-                Any([Air::id(), Water::id()]),
-            ),
+            Displace::rise([Heading::Up, Heading::UpLeft, Heading::UpRight]),
             // Move laterally.
             RandomSwap::adjacent(
-                [EdgeDirection::POINTY_LEFT, EdgeDirection::POINTY_RIGHT],
-                Any([Air::id(), Water::id(), Fire::id()]),
+                [Heading::Left, Heading::Right],
+                StateQuery::any([Air::id(), Water::id(), Fire::id()]),
             ),
             // Another larger chance to condense when not moving
             Near::some_adjacent(
-                Any([Steam::id()]),
+                StateQuery::any([Steam::id()]),
                 5,
                 Chance {
-                    to: Set([Water::id()]),
+                    to: Set([Water::id()].into()),
                     chance: 0.01,
                 },
             ),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::golden::assert_golden;
+
+    #[test]
+    fn rises() {
+        let states = assert_golden(
+            "steam_rises",
+            0,
+            8,
+            r"
+                   . . . .
+                  . . . . .
+                 . . . . . .
+                . . . . . . .
+                 . . . . . .
+                  . ~ ~ ~ .
+                   # # # #
+            ",
+        );
+        // All of it has risen above the middle row
+        assert!(states
+            .iter()
+            .filter(|(_hex, id)| **id == Steam::id())
+            .all(|(hex, _id)| hex.y < 0));
+    }
+
+    #[test]
+    fn bubbles_through_water() {
+        assert_golden(
+            "steam_bubbles_through_water",
+            1,
+            8,
+            r"
+                   . . . .
+                  . . . . .
+                 # W W W W #
+                # W W W W W #
+                 # W W W W #
+                  # ~ ~ ~ #
+                   # # # #
+            ",
+        );
+    }
+}
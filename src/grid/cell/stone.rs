@@ -10,6 +10,32 @@ impl StateInfo for Stone {
     const NAME: &'static str = "Stone";
     const COLOR: HexColor = HexColor::Static(Color::DARK_GRAY);
     const HIDDEN: bool = false;
+    const SYMBOL: char = '#';
+    const DENSITY: f32 = 2.6;
+    const ACID_RESISTANCE: f32 = 0.9;
 }
 
 impl Behavior for Stone {}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::golden::assert_golden;
+
+    #[test]
+    fn holds_sand() {
+        assert_golden(
+            "stone_holds_sand",
+            0,
+            10,
+            r"
+                   S S S S
+                  . S S S .
+                 . . . . . .
+                . . # # # . .
+                 . . . . . .
+                  . . . . .
+                   . . . .
+            ",
+        );
+    }
+}
@@ -1,11 +1,10 @@
 use bevy::prelude::*;
 
-use hexx::EdgeDirection;
 use std::fmt::Debug;
 use unique_type_id::UniqueTypeId;
 
 use super::*;
-use crate::behavior::{StateQuery::*, *};
+use crate::behavior::*;
 
 const BROWN: Color = Color::Rgba {
     red: 0.47,
@@ -14,7 +13,13 @@ const BROWN: Color = Color::Rgba {
     alpha: 1.0,
 };
 
-/// A particle that falls down, and when sand and water are nearby,
+/// Ground a tree can take root in. [`Ash`] from burned trees
+/// fertilizes it as well as [`Sand`].
+fn soil() -> StateSet {
+    [Sand::id(), Ash::id()].into()
+}
+
+/// A particle that falls down, and when sand, ash or water are nearby,
 /// turns into a [`Sapling`].
 #[derive(Debug, UniqueTypeId)]
 #[UniqueTypeIdType = "u8"]
@@ -24,27 +29,35 @@ impl StateInfo for Seed {
     const NAME: &'static str = "Seed";
     const COLOR: HexColor = HexColor::Static(Color::LIME_GREEN);
     const HIDDEN: bool = false;
+    const SYMBOL: char = ',';
+    const DENSITY: f32 = 1.1;
+    const PHASE: Phase = Phase::Powder;
+    const TAGS: Tags = Tags::FLAMMABLE.union(Tags::ORGANIC);
+    const ACID_RESISTANCE: f32 = 0.5;
 }
 
 impl Behavior for Seed {
     fn tick(&self) -> impl Step {
         (
-            // Only attempt to grow when Sand or Water are nearby.
+            // Only attempt to grow when Sand, Ash or Water are nearby.
             Near::any_adjacent(
-                Any([Sand::id(), Water::id()]),
+                StateQuery::any([Sand::id(), Ash::id(), Water::id()]),
                 Chance {
-                    to: Set([Sapling::id()]),
+                    to: Set([Sapling::id()].into()),
                     chance: 1.,
                 },
             ),
+            Blow {
+                open: StateQuery::any([Air::id()]),
+            },
             // Move down
-            RandomSwap::adjacent(
-                [
-                    EdgeDirection::POINTY_BOTTOM_LEFT,
-                    EdgeDirection::POINTY_BOTTOM_RIGHT,
-                ],
-                Any([Air::id(), Wind::id(), Steam::id(), Water::id()]),
-            ),
+            Fall {
+                acceleration: 0.5,
+                terminal: 3.0,
+                splash: 0.0,
+                transfer: 0.0,
+            },
+            Displace::sink([Heading::Down, Heading::DownLeft, Heading::DownRight]),
         )
     }
 }
@@ -57,6 +70,10 @@ pub struct Sapling;
 impl StateInfo for Sapling {
     const NAME: &'static str = "Sapling";
     const COLOR: HexColor = HexColor::Static(Color::DARK_GREEN);
+    const SYMBOL: char = 'i';
+    const DENSITY: f32 = 0.7;
+    const TAGS: Tags = Tags::FLAMMABLE.union(Tags::ORGANIC);
+    const ACID_RESISTANCE: f32 = 0.5;
 }
 
 impl Behavior for Sapling {
@@ -64,36 +81,35 @@ impl Behavior for Sapling {
         // Branch when no sand nearby, try to start branching
         (
             WhileConnected {
-                walkable: Any([Self::id(), Trunk::id(), DeadTrunk::id()]),
-                goal: Any([Sand::id()]),
+                walkable: StateQuery::any([Self::id(), Trunk::id(), DeadTrunk::id()]),
+                goal: StateQuery::any(soil()),
                 then: (
                     // If next to Sand or Dead, change to Trunk
                     Near::any_adjacent(
-                        Any([Self::id()]),
-                        Near::any_adjacent(Any([Sand::id(), DeadTrunk::id()]), Set([Trunk::id()])),
+                        StateQuery::any([Self::id()]),
+                        Near::any_adjacent(
+                            StateQuery::any([Sand::id(), Ash::id(), DeadTrunk::id()]),
+                            Set([Trunk::id()].into()),
+                        ),
                     ),
                     // If next some trunks, turn into a trunk
-                    Near::any_adjacent(Any([Self::id(), Trunk::id()]), Set([Trunk::id()])),
-                    // Otherwise, try to grow
-                    NextTo {
-                        directions: [
-                            EdgeDirection::POINTY_TOP_LEFT,
-                            EdgeDirection::POINTY_TOP_RIGHT,
-                        ],
-                        next: Any([Air::id(), Water::id()]),
-                        // Try to grow
-                        step: Infect {
-                            directions: [
-                                EdgeDirection::POINTY_TOP_LEFT,
-                                EdgeDirection::POINTY_TOP_RIGHT,
-                            ],
-                            open: Any([Air::id(), Sand::id(), Water::id()]),
-                            into: [Self::id()],
-                        },
+                    Near::any_adjacent(
+                        StateQuery::any([Self::id(), Trunk::id()]),
+                        Set([Trunk::id()].into()),
+                    ),
+                    // Otherwise, try to grow up either side
+                    Pattern {
+                        matches: vec![(
+                            Heading::UpLeft.into(),
+                            StateQuery::any([Air::id(), Water::id()]),
+                        )],
+                        writes: vec![(Heading::UpLeft.into(), Rewrite::Set(Self::id().into()))],
+                        symmetry: Symmetry::Mirror,
+                        mirrors: Vec::new(),
                     },
                 ),
             },
-            Set([Trunk::id()]),
+            Set([Trunk::id()].into()),
         )
     }
 }
@@ -107,20 +123,32 @@ pub struct Trunk;
 impl StateInfo for Trunk {
     const NAME: &'static str = "Trunk";
     const COLOR: HexColor = HexColor::Static(BROWN);
+    const SYMBOL: char = 'T';
+    const DENSITY: f32 = 0.7;
+    const TAGS: Tags = Tags::FLAMMABLE.union(Tags::ORGANIC);
+    const ACID_RESISTANCE: f32 = 0.5;
 }
 
 impl Behavior for Trunk {
     fn tick(&self) -> impl Step {
         (
             Near::any(
-                Any([Sand::id(), DeadTrunk::id()]),
+                StateQuery::any([Sand::id(), Ash::id(), DeadTrunk::id()]),
                 5,
-                Set([DeadTrunk::id()]),
+                Set([DeadTrunk::id()].into()),
             ),
-            Near::any(Any([Sand::id()]), 5, AssertFn(|| false)),
+            Near::any(StateQuery::any(soil()), 5, AssertFn(|| false)),
             Choose::half(
-                NotNear::any(Any([BranchLeft::id()]), 4, Set([BranchLeft::id()])),
-                NotNear::any(Any([BranchRight::id()]), 4, Set([BranchRight::id()])),
+                NotNear::any(
+                    StateQuery::any([BranchLeft::id()]),
+                    4,
+                    Set([BranchLeft::id()].into()),
+                ),
+                NotNear::any(
+                    StateQuery::any([BranchRight::id()]),
+                    4,
+                    Set([BranchRight::id()].into()),
+                ),
             ),
         )
     }
@@ -133,66 +161,65 @@ pub struct DeadTrunk;
 impl StateInfo for DeadTrunk {
     const NAME: &'static str = "Dead Trunk";
     const COLOR: HexColor = HexColor::Static(BROWN);
+    const SYMBOL: char = 'D';
+    const DENSITY: f32 = 0.6;
+    const TAGS: Tags = Tags::FLAMMABLE.union(Tags::ORGANIC);
+    const ACID_RESISTANCE: f32 = 0.5;
 }
 
 impl Behavior for DeadTrunk {}
 
-#[derive(Debug, UniqueTypeId)]
-#[UniqueTypeIdType = "u8"]
-struct Branch {
-    direction: EdgeDirection,
-    grow_into: StateId,
-}
-
-impl Step for Branch {
-    fn apply(self, hex: Hex, states: &BoardState, rng: f32) -> Option<BoardSlice> {
-        (
-            // When next to other tree components, just stop doing anything.
-            Near::some_adjacent(
-                Any([
-                    BranchLeft::id(),
-                    BranchRight::id(),
-                    DeadTrunk::id(),
-                    Trunk::id(),
-                    Twig::id(),
-                ]),
-                2,
-                Set([DeadTrunk::id()]),
+/// The behavior shared by [`BranchLeft`] and [`BranchRight`], written
+/// for a left branch and mirrored for the right.
+fn branch() -> impl Step {
+    (
+        // When next to other tree components, just stop doing anything.
+        Near::some_adjacent(
+            StateQuery::any([
+                BranchLeft::id(),
+                BranchRight::id(),
+                DeadTrunk::id(),
+                Trunk::id(),
+                Twig::id(),
+            ]),
+            2,
+            Set([DeadTrunk::id()].into()),
+        ),
+        // When near other branches, also stop doing anything
+        Near::any(
+            StateQuery::any([BranchLeft::id(), BranchRight::id()]),
+            25,
+            Set([DeadTrunk::id()].into()),
+        ),
+        // Otherwise, try and grow outward, stopping when blocked.
+        Weighted::fall_through([
+            // Grow
+            (
+                0.8,
+                Pattern {
+                    matches: vec![
+                        (Hex::ZERO.into(), StateQuery::any([BranchLeft::id()])),
+                        (Heading::UpLeft.into(), StateQuery::any([Air::id()])),
+                    ],
+                    writes: vec![(
+                        Heading::UpLeft.into(),
+                        Rewrite::Set(BranchLeft::id().into()),
+                    )],
+                    symmetry: Symmetry::Mirror,
+                    mirrors: vec![(BranchLeft::id(), BranchRight::id())],
+                }
+                .boxed(),
             ),
-            // When near other branches, also stop doing anything
-            Near::any(
-                Any([BranchLeft::id(), BranchRight::id()]),
-                25,
-                Set([DeadTrunk::id()]),
+            // Chance to stop growing
+            (
+                0.2,
+                Choose::half(Set([Twig::id()].into()), Set([DeadTrunk::id()].into())).boxed(),
             ),
-            // Otherwise, try and grow right.
-            Choose {
-                // Grow
-                a: When(
-                    |hex, states, _rng| {
-                        states.is_state(hex.neighbor(self.direction), &Any([Air::id()]))
-                    },
-                    Infect {
-                        directions: [self.direction],
-                        open: Any([
-                            Air::id(),
-                            Sand::id(),
-                            Water::id(),
-                            Sapling::id(),
-                            Seed::id(),
-                        ]),
-                        into: [self.grow_into],
-                    },
-                ),
-                // Chance to stop growing
-                b: Choose::half(Set([Twig::id()]), Set([DeadTrunk::id()])),
-                chance: 0.8,
-            },
-        )
-            .apply(hex, states, rng)
-    }
+        ]),
+    )
 }
 
+/// A branch growing up and to the left.
 #[derive(Debug, UniqueTypeId)]
 #[UniqueTypeIdType = "u8"]
 pub struct BranchLeft;
@@ -200,17 +227,19 @@ pub struct BranchLeft;
 impl StateInfo for BranchLeft {
     const NAME: &'static str = "BranchLeft";
     const COLOR: HexColor = HexColor::Static(BROWN);
+    const SYMBOL: char = '\\';
+    const DENSITY: f32 = 0.7;
+    const TAGS: Tags = Tags::FLAMMABLE.union(Tags::ORGANIC);
+    const ACID_RESISTANCE: f32 = 0.5;
 }
 
 impl Behavior for BranchLeft {
     fn tick(&self) -> impl Step {
-        Branch {
-            direction: EdgeDirection::POINTY_TOP_LEFT,
-            grow_into: Self::id(),
-        }
+        branch()
     }
 }
 
+/// The mirror image of a [`BranchLeft`], growing up and to the right.
 #[derive(Debug, UniqueTypeId)]
 #[UniqueTypeIdType = "u8"]
 pub struct BranchRight;
@@ -218,14 +247,15 @@ pub struct BranchRight;
 impl StateInfo for BranchRight {
     const NAME: &'static str = "BranchRight";
     const COLOR: HexColor = HexColor::Static(BROWN);
+    const SYMBOL: char = '/';
+    const DENSITY: f32 = 0.7;
+    const TAGS: Tags = Tags::FLAMMABLE.union(Tags::ORGANIC);
+    const ACID_RESISTANCE: f32 = 0.5;
 }
 
 impl Behavior for BranchRight {
     fn tick(&self) -> impl Step {
-        Branch {
-            direction: EdgeDirection::POINTY_TOP_RIGHT,
-            grow_into: Self::id(),
-        }
+        branch()
     }
 }
 
@@ -236,6 +266,10 @@ pub struct Twig;
 impl StateInfo for Twig {
     const NAME: &'static str = "Twig";
     const COLOR: HexColor = HexColor::Static(BROWN);
+    const SYMBOL: char = 'y';
+    const DENSITY: f32 = 0.6;
+    const TAGS: Tags = Tags::FLAMMABLE.union(Tags::ORGANIC);
+    const ACID_RESISTANCE: f32 = 0.5;
 }
 
 impl Behavior for Twig {
@@ -243,9 +277,9 @@ impl Behavior for Twig {
         Chance {
             chance: 0.1,
             to: Infect {
-                directions: EdgeDirection::ALL_DIRECTIONS,
-                open: Any([Air::id()]),
-                into: [Leaf::id()],
+                directions: Directions::ALL,
+                open: StateQuery::any([Air::id()]),
+                into: [Leaf::id()].into(),
             },
         }
     }
@@ -258,24 +292,28 @@ pub struct Leaf;
 impl StateInfo for Leaf {
     const NAME: &'static str = "Leaf";
     const COLOR: HexColor = HexColor::Static(Color::GREEN);
+    const SYMBOL: char = '*';
+    const DENSITY: f32 = 0.4;
+    const TAGS: Tags = Tags::FLAMMABLE.union(Tags::ORGANIC);
+    const ACID_RESISTANCE: f32 = 0.5;
 }
 
 impl Behavior for Leaf {
     fn tick(&self) -> impl Step {
         WhileConnected {
-            walkable: Any([Self::id(), Trunk::id(), DeadTrunk::id()]),
-            goal: Any([Sand::id()]),
+            walkable: StateQuery::any([Self::id(), Trunk::id(), DeadTrunk::id()]),
+            goal: StateQuery::any(soil()),
             then: (
                 Near::new(
-                    Any([Self::id()]),
+                    StateQuery::any([Self::id()]),
                     20,
                     50,
                     (
                         Chance {
                             to: Infect {
-                                directions: EdgeDirection::ALL_DIRECTIONS,
-                                open: Any([Air::id()]),
-                                into: [Wind::id()],
+                                directions: Directions::ALL,
+                                open: StateQuery::any([Air::id()]),
+                                into: [Wind::id()].into(),
                             },
                             chance: 0.01,
                         },
@@ -283,16 +321,140 @@ impl Behavior for Leaf {
                     ),
                 ),
                 Near::new(
-                    Any([Twig::id()]),
+                    StateQuery::any([Twig::id()]),
                     5,
                     1,
                     Infect {
-                        directions: EdgeDirection::ALL_DIRECTIONS,
-                        open: Any([Air::id()]),
-                        into: [Self::id()],
+                        directions: Directions::ALL,
+                        open: StateQuery::any([Air::id()]),
+                        into: [Self::id()].into(),
                     },
                 ),
             ),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_turborand::{GlobalRng, SeededCore};
+    use hexx::Hex;
+
+    use super::*;
+    use crate::grid::golden::{self, assert_golden};
+
+    #[test]
+    fn blocked_branch_stops_growing() {
+        let registry = CellRegistry::builtin();
+        for seed in 0..20 {
+            let mut states = golden::parse(
+                &registry,
+                r"
+                   . . .
+                  . # # .
+                 . . / . .
+                  . . . .
+                   . . .
+                ",
+            );
+            golden::tick(&mut states, &registry, &mut GlobalRng::with_seed(seed));
+            let stopped = states.get_current(Hex::ZERO).unwrap();
+            assert!(
+                [Twig::id(), DeadTrunk::id()].contains(stopped),
+                "Blocked branch became {} with seed {seed}",
+                registry[stopped].name,
+            );
+        }
+    }
+
+    #[test]
+    fn branches_grow_their_own_way() {
+        let registry = CellRegistry::builtin();
+        for (branch_id, heading) in [
+            (BranchLeft::id(), Heading::UpLeft),
+            (BranchRight::id(), Heading::UpRight),
+        ] {
+            let mut states =
+                golden::parse(&registry, "  . . .\n . . . .\n. . . . .\n . . . .\n  . . .");
+            states.set_next(Hex::ZERO, branch_id);
+            states.commit();
+            let grows = Hex::ZERO.neighbor(states.frame(Hex::ZERO).edge(heading).unwrap());
+            let mut grew = false;
+            for seed in 0..20 {
+                let slice = branch()
+                    .apply(Hex::ZERO, &states, &mut CellRng::with_seed(seed))
+                    .unwrap();
+                if slice.cells == vec![(grows, branch_id)] {
+                    grew = true;
+                } else {
+                    assert!(
+                        [Twig::id(), DeadTrunk::id()]
+                            .map(|id| vec![(Hex::ZERO, id)])
+                            .contains(&slice.cells),
+                        "{} did something else: {slice:?}",
+                        registry[&branch_id].name,
+                    );
+                }
+            }
+            assert!(grew, "{} never grew", registry[&branch_id].name);
+        }
+    }
+
+    #[test]
+    fn seed_sprouts() {
+        assert_golden(
+            "tree_seed_sprouts",
+            0,
+            6,
+            r"
+                   . . , .
+                  . . . . .
+                 . . . . . .
+                . . . . . . .
+                 S S S S S S
+                  # # # # #
+                   # # # #
+            ",
+        );
+    }
+
+    #[test]
+    fn sapling_grows() {
+        assert_golden(
+            "tree_sapling_grows",
+            1,
+            30,
+            r"
+                     . . . . . .
+                    . . . . . . .
+                   . . . . . . . .
+                  . . . . . . . . .
+                 . . . . . . . . . .
+                . . . . . i . . . . .
+                 S S S S S S S S S S
+                  # # # # # # # # #
+                   # # # # # # # #
+                    # # # # # # #
+                     # # # # # #
+            ",
+        );
+    }
+
+    #[test]
+    fn twig_grows_leaves() {
+        assert_golden(
+            "tree_twig_grows_leaves",
+            2,
+            40,
+            r"
+                   . . . .
+                  . . . . .
+                 . . . y . .
+                . . . T . . .
+                 . . . T . .
+                  S S S S S
+                   # # # #
+            ",
+        );
+    }
+}
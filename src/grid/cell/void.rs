@@ -1,9 +1,8 @@
 use bevy::prelude::*;
-use hexx::EdgeDirection;
 use unique_type_id::UniqueTypeId;
 
 use super::*;
-use crate::behavior::{StateQuery::*, *};
+use crate::behavior::*;
 
 #[derive(UniqueTypeId)]
 #[UniqueTypeIdType = "u8"]
@@ -13,13 +12,38 @@ impl StateInfo for Void {
     const NAME: &'static str = "Void";
     const COLOR: HexColor = HexColor::Static(Color::rgb(0.2, 0.0, 0.2));
     const HIDDEN: bool = false;
+    const SYMBOL: char = 'V';
+    const DENSITY: f32 = 0.0;
 }
 impl Behavior for Void {
     fn tick(&self) -> impl Step {
         Infect {
-            directions: EdgeDirection::ALL_DIRECTIONS,
-            open: Except([Air::id(), Void::id()]),
-            into: [Air::id()],
+            directions: Directions::ALL,
+            open: StateQuery::except([Air::id(), Void::id()]),
+            into: [Air::id()].into(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::golden::assert_golden;
+
+    #[test]
+    fn consumes_neighbors() {
+        assert_golden(
+            "void_consumes_neighbors",
+            0,
+            10,
+            r"
+                   S S S S
+                  W W W W W
+                 # # V V # #
+                . . . . . . .
+                 . . . . . .
+                  . . . . .
+                   . . . .
+            ",
+        );
+    }
+}
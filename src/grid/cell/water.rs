@@ -1,9 +1,8 @@
 use bevy::prelude::*;
-use hexx::EdgeDirection;
 use unique_type_id::UniqueTypeId;
 
 use super::*;
-use crate::behavior::{StateQuery::*, *};
+use crate::behavior::*;
 
 #[derive(UniqueTypeId)]
 #[UniqueTypeIdType = "u8"]
@@ -28,79 +27,158 @@ impl StateInfo for Water {
         scale: Vec2::splat(0.2),
     };
     const HIDDEN: bool = false;
+    const SYMBOL: char = 'W';
+    const CONSERVED: bool = true;
+    const DENSITY: f32 = 1.0;
+    const PHASE: Phase = Phase::Liquid;
 }
 impl Behavior for Water {
     fn tick(&self) -> impl Step {
         (
-            // Gravity
-            RandomSwap::adjacent(
-                [
-                    EdgeDirection::POINTY_BOTTOM_LEFT,
-                    EdgeDirection::POINTY_BOTTOM_RIGHT,
-                ],
-                Any([Air::id()]),
+            // Boil on lava, cooling it into stone
+            Near::any_adjacent(
+                StateQuery::any([Lava::id()]),
+                All((
+                    Set([Steam::id()].into()),
+                    Infect {
+                        directions: Directions::ALL,
+                        open: StateQuery::any([Lava::id()]),
+                        into: [Stone::id()].into(),
+                    },
+                )),
+            ),
+            // Slowly freeze on ice
+            Near::any_adjacent(
+                StateQuery::any([Ice::id()]),
+                Chance {
+                    to: Set([Ice::id()].into()),
+                    chance: 0.01,
+                },
             ),
+            // Rise through channels when pushed by a higher surface
+            Squeeze {
+                directions: [Heading::Up, Heading::UpLeft, Heading::UpRight].into(),
+                open: StateQuery::any([Air::id()]),
+            },
+            // Gravity
+            Fall {
+                acceleration: 0.5,
+                terminal: 4.0,
+                splash: 0.5,
+                transfer: 0.25,
+            },
+            Displace::sink([Heading::Down, Heading::DownLeft, Heading::DownRight]),
             // Move through thick materials
             Chance {
                 chance: 0.5,
                 to: RandomSwap::adjacent(
-                    [
-                        EdgeDirection::POINTY_TOP_LEFT,
-                        EdgeDirection::POINTY_TOP_RIGHT,
-                    ],
-                    Any([Sand::id()]),
+                    [Heading::Up, Heading::UpLeft, Heading::UpRight],
+                    StateQuery::any([Sand::id()]),
                 ),
             },
             // Below air
             NextTo {
-                directions: [
-                    EdgeDirection::POINTY_TOP_LEFT,
-                    EdgeDirection::POINTY_TOP_RIGHT,
-                ],
-                next: Any([Air::id()]),
+                directions: [Heading::Up, Heading::UpLeft, Heading::UpRight].into(),
+                next: StateQuery::any([Air::id()]),
                 // Evaporate
                 step: Chance {
-                    to: Set([Steam::id()]),
+                    to: Set([Steam::id()].into()),
                     chance: 0.001,
                 },
             },
             // Drag things
             Drag {
                 directions: [
-                    EdgeDirection::POINTY_LEFT,
-                    EdgeDirection::POINTY_RIGHT,
-                    EdgeDirection::POINTY_BOTTOM_LEFT,
-                    EdgeDirection::POINTY_BOTTOM_RIGHT,
-                ],
-                open: Any([Air::id(), Self::id()]),
-                drag: Any([Sand::id()]),
+                    Heading::Left,
+                    Heading::Right,
+                    Heading::Down,
+                    Heading::DownLeft,
+                    Heading::DownRight,
+                ]
+                .into(),
+                open: StateQuery::any([Air::id(), Self::id()]),
+                drag: StateQuery::any([Sand::id()]),
             },
             // Move laterally
             Choose {
-                a: RandomSwap {
-                    directions: [
-                        EdgeDirection::POINTY_LEFT,
-                        EdgeDirection::POINTY_RIGHT,
-                        EdgeDirection::POINTY_BOTTOM_LEFT,
-                        EdgeDirection::POINTY_BOTTOM_RIGHT,
-                    ],
-                    open: Any([Air::id()]),
-                    distance: 5,
-                    collide: true,
-                },
+                // Under anything lighter, so lighter liquids float on top
+                a: Displace::sink([
+                    Heading::Left,
+                    Heading::Right,
+                    Heading::Down,
+                    Heading::DownLeft,
+                    Heading::DownRight,
+                ]),
                 b: RandomSwap {
                     directions: [
-                        EdgeDirection::POINTY_LEFT,
-                        EdgeDirection::POINTY_RIGHT,
-                        EdgeDirection::POINTY_BOTTOM_LEFT,
-                        EdgeDirection::POINTY_BOTTOM_RIGHT,
-                    ],
-                    open: Any([Air::id(), Self::id()]),
-                    distance: 5,
-                    collide: true,
+                        Heading::Left,
+                        Heading::Right,
+                        Heading::Down,
+                        Heading::DownLeft,
+                        Heading::DownRight,
+                    ]
+                    .into(),
+                    open: StateQuery::any([Air::id(), Self::id()]),
                 },
                 chance: 0.99,
             },
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::golden::{self, assert_golden};
+
+    const BLOB: &str = r"
+                    . . . . .
+                   # . W W . #
+                  # . . W W . #
+                 # . . W W W . #
+                # . . . W . . . #
+                 # . . . . . . #
+                  # . . . . . #
+                   # # # # # #
+                    # # # # #
+            ";
+
+    #[test]
+    fn levels() {
+        assert_golden("water_levels", 0, 60, BLOB);
+    }
+
+    #[test]
+    fn comes_to_rest_level() {
+        for seed in 0..10 {
+            let states = golden::run(seed, 60, BLOB, |_states| {});
+            // The floor fills up and the rest share the row above it,
+            // less any that evaporated.
+            let rows = states
+                .iter()
+                .filter(|(_hex, id)| **id == Water::id())
+                .map(|(hex, _id)| hex.y)
+                .collect::<Vec<_>>();
+            assert_eq!(rows.iter().filter(|y| **y == 2).count(), 5, "seed {seed}");
+            assert!(rows.iter().all(|y| *y >= 1), "seed {seed}");
+        }
+    }
+
+    #[test]
+    fn seeps_into_sand() {
+        assert_golden(
+            "water_seeps_into_sand",
+            3,
+            20,
+            r"
+                   . . . .
+                  . W W W .
+                 . W W W W .
+                . S S S S S .
+                 # S S S S #
+                  # S S S #
+                   # # # #
+            ",
+        );
+    }
+}
@@ -1,9 +1,12 @@
 use bevy::prelude::*;
-use hexx::EdgeDirection;
 
 use super::*;
-use crate::behavior::{StateQuery::*, *};
+use crate::behavior::*;
 
+/// A gust that spreads, drags things along and moves with the
+/// [`WindField`], and dies out in calm air.
+///
+/// [`WindField`]: crate::grid::WindField
 #[derive(UniqueTypeId)]
 #[UniqueTypeIdType = "u8"]
 pub struct Wind;
@@ -17,51 +20,134 @@ impl StateInfo for Wind {
         alpha: 0.01,
     });
     const HIDDEN: bool = false;
+    const SYMBOL: char = '<';
+    const DENSITY: f32 = 0.001;
+    const PHASE: Phase = Phase::Gas;
 }
 
 impl Behavior for Wind {
     fn tick(&self) -> impl Step {
         (
-            Chance {
-                chance: 0.1,
-                to: Choose::half(
-                    // Dissipate
-                    Set([Air::id()]),
-                    // Create more wind
-                    Infect {
-                        directions: [
-                            EdgeDirection::POINTY_LEFT,
-                            EdgeDirection::POINTY_BOTTOM_LEFT,
-                            EdgeDirection::POINTY_TOP_LEFT,
-                        ],
-                        open: Any([Air::id()]),
-                        into: [Self::id()],
-                    },
-                ),
-            },
-            Offscreen([
-                EdgeDirection::POINTY_LEFT,
-                EdgeDirection::POINTY_BOTTOM_LEFT,
-                EdgeDirection::POINTY_TOP_LEFT,
-            ]),
-            Drag {
-                directions: [
-                    EdgeDirection::POINTY_LEFT,
-                    EdgeDirection::POINTY_BOTTOM_LEFT,
-                    EdgeDirection::POINTY_TOP_LEFT,
-                ],
-                open: Any([Air::id(), Self::id()]),
-                drag: Any([Water::id(), Fire::id(), Sand::id()]),
+            Blow {
+                open: StateQuery::any([Air::id()]),
             },
-            RandomSwap::adjacent(
-                [
-                    EdgeDirection::POINTY_LEFT,
-                    EdgeDirection::POINTY_BOTTOM_LEFT,
-                    EdgeDirection::POINTY_TOP_LEFT,
-                ],
-                Any([Air::id(), Self::id()]),
-            ),
-            Set([Air::id()]),
+            Downwind(|directions| {
+                (
+                    Weighted::new([
+                        // Dissipate
+                        (0.05, Set([Air::id()].into()).boxed()),
+                        // Create more wind
+                        (
+                            0.05,
+                            Infect {
+                                directions,
+                                open: StateQuery::any([Air::id()]),
+                                into: [Self::id()].into(),
+                            }
+                            .boxed(),
+                        ),
+                        (0.9, Noop.boxed()),
+                    ]),
+                    Offscreen(directions),
+                    Drag {
+                        directions,
+                        open: StateQuery::any([Air::id(), Self::id()]),
+                        drag: StateQuery::any([
+                            Water::id(),
+                            Fire::id(),
+                            Sand::id(),
+                            Smoke::id(),
+                            ThickSmoke::id(),
+                        ]),
+                    },
+                    RandomSwap::adjacent(directions, StateQuery::any([Air::id(), Self::id()])),
+                )
+            }),
+            Set([Air::id()].into()),
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::Vec2;
+
+    use super::*;
+    use crate::grid::golden::{self, assert_golden_with};
+
+    /// Blow `wind` over the whole board.
+    fn blow(wind: Vec2) -> impl FnOnce(&mut BoardState) {
+        move |states| {
+            let count = states.iter().count();
+            states.set_wind(vec![wind; count]);
+        }
+    }
+
+    #[test]
+    fn blows_left() {
+        assert_golden_with(
+            "wind_blows_left",
+            0,
+            6,
+            r"
+                   . . . .
+                  . . . . .
+                 . . . . . .
+                . . . . . . <
+                 . . . . . .
+                  # # # # #
+                   # # # #
+            ",
+            blow(Vec2::new(-0.2, 0.0)),
+        );
+    }
+
+    #[test]
+    fn drags_sand() {
+        assert_golden_with(
+            "wind_drags_sand",
+            1,
+            4,
+            r"
+                   . . . .
+                  . . . . .
+                 . . . . . .
+                . . . . S S <
+                 . . . . S <
+                  # # # # #
+                   # # # #
+            ",
+            blow(Vec2::new(-0.2, 0.0)),
+        );
+    }
+
+    #[test]
+    fn follows_the_field() {
+        let board = r"
+               . . . .
+              . . . . .
+             . . . . . .
+            < . . . . . .
+             . . . . . .
+              . . . . .
+               . . . .
+        ";
+        // Where the gusts are across the board after some ticks.
+        let gusts = |wind, ticks| {
+            let states = golden::run(0, ticks, board, blow(wind));
+            states
+                .iter()
+                .filter(|(_hex, id)| **id == Wind::id())
+                .map(|(hex, _id)| states.layout().hex_to_world_pos(hex).x)
+                .collect::<Vec<_>>()
+        };
+        let start = gusts(Vec2::ZERO, 0)[0];
+        // Nothing to blow along, so the gust dies out.
+        assert!(gusts(Vec2::ZERO, 1).is_empty());
+        // Blown off the left edge of the board.
+        assert!(gusts(Vec2::new(-0.2, 0.0), 1).is_empty());
+        let right = gusts(Vec2::new(0.2, 0.0), 1);
+        assert!(!right.is_empty());
+        assert!(right.iter().any(|x| *x > start));
+    }
+}
@@ -0,0 +1,235 @@
+use std::{borrow::Cow, fmt, mem, sync::Mutex};
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_inspector_egui::{inspector_options::ReflectInspectorOptions, InspectorOptions};
+use hexx::Hex;
+
+use crate::behavior::StateId;
+
+use super::{
+    cell::{BoardSlice, CellRegistry},
+    BoardState,
+};
+
+/// When enabled, states marked as conserved in the [`CellRegistry`]
+/// can only be moved around the board. Any rule that would create or
+/// destroy one of them is skipped.
+#[derive(Reflect, Resource, Default, InspectorOptions)]
+#[reflect(Resource, InspectorOptions)]
+pub struct Conservation {
+    pub enabled: bool,
+}
+
+/// The number of each conserved state on the board.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Census(HashMap<StateId, usize>);
+
+impl Census {
+    /// Count the conserved states as they will be after the next
+    /// commit.
+    pub fn take(states: &BoardState, registry: &CellRegistry) -> Self {
+        let mut census = HashMap::default();
+        for hex in &states.positions {
+            let id = states.get_next(*hex).unwrap();
+            if registry[&id].conserved {
+                *census.entry(id).or_default() += 1;
+            }
+        }
+        Self(census)
+    }
+}
+
+/// A rule that tried to create or destroy a conserved state.
+#[derive(Debug, Clone)]
+pub struct Violation {
+    /// Name of the state whose behavior made the slice.
+    pub rule: Cow<'static, str>,
+    /// Name of the conserved state.
+    pub state: Cow<'static, str>,
+    /// How many of `state` would have been created, or destroyed when
+    /// negative.
+    pub change: isize,
+    /// Where the rule was applied.
+    pub hex: Hex,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verb = if self.change > 0 { "create" } else { "destroy" };
+        write!(
+            f,
+            "{} at {:?} tried to {} {} {}",
+            self.rule,
+            self.hex,
+            verb,
+            self.change.abs(),
+            self.state
+        )
+    }
+}
+
+/// The census changed during a tick even though every slice was
+/// checked.
+#[derive(Debug)]
+pub struct CensusError {
+    pub before: Census,
+    pub after: Census,
+    /// Rules that were stopped from breaking conservation this tick.
+    pub violations: Vec<Violation>,
+}
+
+impl fmt::Display for CensusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Conserved states changed from {:?} to {:?}",
+            self.before, self.after
+        )?;
+        for violation in &self.violations {
+            write!(f, "\n  {violation}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Tracks conserved states over a single tick.
+#[derive(Resource, Default)]
+pub struct ConservationCheck {
+    census: Census,
+    violations: Mutex<Vec<Violation>>,
+}
+
+impl ConservationCheck {
+    /// Take a [`Census`] before any cells are ticked.
+    pub fn begin(&mut self, states: &BoardState, registry: &CellRegistry) {
+        self.census = Census::take(states, registry);
+        self.violations.get_mut().unwrap().clear();
+    }
+
+    /// Check that `slice`, made by the behavior of `rule` at `hex`,
+    /// doesn't change the amount of any conserved state.
+    ///
+    /// Returns `false` and records a [`Violation`] when it does.
+    pub fn allows(
+        &self,
+        rule: StateId,
+        hex: Hex,
+        slice: &BoardSlice,
+        states: &BoardState,
+        registry: &CellRegistry,
+    ) -> bool {
+        let mut changes = HashMap::<StateId, isize>::default();
+        for (hex, id) in slice.iter() {
+            if let Some(before) = states.get_next(*hex) {
+                if registry[&before].conserved {
+                    *changes.entry(before).or_default() -= 1;
+                }
+            }
+            if registry[id].conserved {
+                *changes.entry(*id).or_default() += 1;
+            }
+        }
+
+        let Some((state, change)) = changes.into_iter().find(|(_id, change)| *change != 0) else {
+            return true;
+        };
+        if let Ok(mut violations) = self.violations.lock() {
+            violations.push(Violation {
+                rule: registry[&rule].name.clone(),
+                state: registry[&state].name.clone(),
+                change,
+                hex,
+            });
+        }
+        false
+    }
+
+    /// Rules that were stopped from breaking conservation this tick.
+    pub fn violations(&mut self) -> Vec<Violation> {
+        mem::take(self.violations.get_mut().unwrap())
+    }
+
+    /// Assert the census is unchanged after the board was committed.
+    pub fn finish(
+        &mut self,
+        states: &BoardState,
+        registry: &CellRegistry,
+    ) -> Result<(), CensusError> {
+        let after = Census::take(states, registry);
+        if after == self.census {
+            Ok(())
+        } else {
+            Err(CensusError {
+                before: mem::replace(&mut self.census, after.clone()),
+                after,
+                violations: self.violations(),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_turborand::GlobalRng;
+    use unique_type_id::UniqueTypeId as _;
+
+    use super::*;
+    use crate::grid::{
+        cell::{Void, Water},
+        golden, tick_cells, Schedule,
+    };
+
+    /// Tick the board like [`golden::tick`], skipping any slice the
+    /// `check` doesn't allow.
+    fn tick(
+        states: &mut BoardState,
+        registry: &CellRegistry,
+        check: &mut ConservationCheck,
+        rng: &mut GlobalRng,
+    ) -> Result<(), CensusError> {
+        check.begin(states, registry);
+        tick_cells(states, registry, Some(check), rng, Schedule::Sequential);
+        states.commit();
+        check.finish(states, registry)
+    }
+
+    #[test]
+    fn water_is_conserved() {
+        let registry = CellRegistry::builtin();
+        let mut states = golden::parse(
+            &registry,
+            r"
+                   . . . .
+                  . . . . .
+                 # W W W W #
+                # W W W W W #
+                 # W W V W #
+                  # W W W #
+                   # # # #
+            ",
+        );
+        let mut check = ConservationCheck::default();
+        let mut rng = GlobalRng::with_seed(0);
+        let water = Census::take(&states, &registry).0[&Water::id()];
+        let mut violations = Vec::new();
+        for _ in 0..200 {
+            tick(&mut states, &registry, &mut check, &mut rng).unwrap();
+            violations.extend(check.violations());
+        }
+        assert_eq!(Census::take(&states, &registry).0[&Water::id()], water);
+        assert!(violations.iter().any(|violation| violation.rule == "Void"));
+    }
+
+    #[test]
+    fn unchecked_changes_are_reported() {
+        let registry = CellRegistry::builtin();
+        let mut states = golden::parse(&registry, " . .\n. W .\n . .");
+        let mut check = ConservationCheck::default();
+        check.begin(&states, &registry);
+        states.apply(BoardSlice::new(vec![(Hex::ZERO, Void::id())]));
+        states.commit();
+        let error = check.finish(&states, &registry).unwrap_err();
+        assert_eq!(error.before.0.get(&Water::id()), Some(&1));
+        assert_eq!(error.after.0.get(&Water::id()), None);
+    }
+}
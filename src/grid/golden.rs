@@ -0,0 +1,173 @@
+//! Golden-file tests for cell behaviors.
+//!
+//! A board is written as ASCII art, one [`StateInfo::SYMBOL`] per
+//! hex, with each row indented by half a hex so the text lines up
+//! with the pointy layout of the board:
+//!
+//! ```text
+//!   . . .
+//!  . S . .
+//! . . . . .
+//!  # # # #
+//!   # # #
+//! ```
+//!
+//! The board is ticked a number of times with a seeded rng and the
+//! result is compared against a snapshot in `src/grid/cell/snapshots`.
+//! Run `just test --bless` to write the snapshots instead of comparing
+//! against them. The test harness rejects arguments it doesn't
+//! recognize, so `just` turns the flag into the `BLESS` environment
+//! variable, which works with plain `BLESS=1 cargo test` as well.
+//!
+//! [`StateInfo::SYMBOL`]: super::cell::StateInfo::SYMBOL
+
+use std::{env, fs, path::PathBuf};
+
+use bevy_turborand::{DelegatedRng, GlobalRng};
+use hexx::Hex;
+
+use super::{cell::CellRegistry, tick_cells, BoardState, Schedule};
+use crate::behavior::StateId;
+
+/// Parse an ASCII board into a [`BoardState`].
+///
+/// The radius of the board is taken from the number of rows.
+pub fn parse(registry: &CellRegistry, text: &str) -> BoardState {
+    let rows = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>();
+    assert!(
+        rows.len() % 2 == 1,
+        "A board needs an odd number of rows, found {}",
+        rows.len()
+    );
+    let radius = rows.len() as i32 / 2;
+    let mut states = BoardState::new(radius as u32, registry);
+    for (row, y) in rows.into_iter().zip(-radius..=radius) {
+        let symbols = row.split_whitespace().collect::<Vec<_>>();
+        let xs = (-radius).max(-radius - y)..=radius.min(radius - y);
+        assert_eq!(
+            symbols.len(),
+            xs.clone().count(),
+            "Row {y} has the wrong number of hexes: {row:?}"
+        );
+        for (symbol, x) in symbols.into_iter().zip(xs) {
+            let mut chars = symbol.chars();
+            let (Some(symbol), None) = (chars.next(), chars.next()) else {
+                panic!("Expected a single character per hex in row {y}: {row:?}");
+            };
+            let id = registry
+                .iter()
+                .find(|(_id, entry)| entry.symbol == symbol)
+                .map(|(id, _entry)| *id)
+                .unwrap_or_else(|| panic!("No state is drawn with {symbol:?}"));
+            states.set_next(Hex::new(x, y), id);
+        }
+    }
+    states.commit();
+    states
+}
+
+/// Draw the current state of the board as ASCII.
+pub fn render(registry: &CellRegistry, states: &BoardState) -> String {
+    let radius = states.bounds().radius as i32;
+    let mut text = String::new();
+    for y in -radius..=radius {
+        text.push_str(&" ".repeat(y.unsigned_abs() as usize));
+        let row = ((-radius).max(-radius - y)..=radius.min(radius - y))
+            .map(|x| registry[states.get_current(Hex::new(x, y)).unwrap()].symbol)
+            .map(String::from)
+            .collect::<Vec<_>>();
+        text.push_str(&row.join(" "));
+        text.push('\n');
+    }
+    text
+}
+
+/// Tick every cell on the board once and commit the result.
+///
+/// Uses the same scheduling as [`super::sim_system`], but visits
+/// cells one at a time so the result only depends on the seed.
+pub fn tick(states: &mut BoardState, registry: &CellRegistry, rng: &mut impl DelegatedRng) {
+    tick_cells(states, registry, None, rng, Schedule::Sequential);
+    states.commit();
+}
+
+/// How many cells on the board are in state `id`.
+pub fn count(states: &BoardState, id: StateId) -> usize {
+    states.iter().filter(|(_hex, other)| **other == id).count()
+}
+
+/// Parse `board`, let `setup` change it, like giving cells data or
+/// setting the wind, then tick it `ticks` times with the rng seeded
+/// with `seed`.
+pub fn run(
+    seed: u64,
+    ticks: usize,
+    board: &str,
+    setup: impl FnOnce(&mut BoardState),
+) -> BoardState {
+    let registry = CellRegistry::builtin();
+    let mut states = parse(&registry, board);
+    setup(&mut states);
+    states.commit();
+    let mut rng = GlobalRng::with_seed(seed);
+    for _ in 0..ticks {
+        tick(&mut states, &registry, &mut rng);
+    }
+    states
+}
+
+/// Run `board` for `ticks` and compare it with the snapshot `name`.
+///
+/// Returns the board, so tests can check what matters about it
+/// directly as well. Those checks still run when blessing.
+pub fn assert_golden(name: &str, seed: u64, ticks: usize, board: &str) -> BoardState {
+    assert_golden_with(name, seed, ticks, board, |_states| {})
+}
+
+/// Like [`assert_golden`], but lets `setup` change the board before
+/// the first tick, like setting the wind.
+pub fn assert_golden_with(
+    name: &str,
+    seed: u64,
+    ticks: usize,
+    board: &str,
+    setup: impl FnOnce(&mut BoardState),
+) -> BoardState {
+    let states = run(seed, ticks, board, setup);
+    let actual = render(&CellRegistry::builtin(), &states);
+
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/grid/cell/snapshots")
+        .join(name)
+        .with_extension("txt");
+    if env::var_os("BLESS").is_some() {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, &actual).unwrap();
+        return states;
+    }
+
+    let expected = fs::read_to_string(&path).unwrap_or_else(|_| {
+        panic!(
+            "Missing snapshot {}, run `just test --bless` to create it",
+            path.display()
+        )
+    });
+    assert!(
+        expected == actual,
+        "Board does not match snapshot {name} after {ticks} ticks\n\
+         expected:\n{expected}\n\
+         actual:\n{actual}"
+    );
+    states
+}
+
+#[test]
+fn parse_render_roundtrip() {
+    let registry = CellRegistry::builtin();
+    let board = "  . . .\n . S W .\n. # F ~ .\n < , V .\n  E * T\n";
+    assert_eq!(render(&registry, &parse(&registry, board)), board);
+}
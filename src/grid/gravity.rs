@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use bevy_inspector_egui::{inspector_options::ReflectInspectorOptions, InspectorOptions};
+use hexx::{Hex, HexBounds};
+
+use super::BoardState;
+
+/// Which way is down for the materials on the board.
+///
+/// Rules are written as if down is straight down the screen, and are
+/// turned in sixths of a turn to match the gravity of each cell. With
+/// no gravity, rules that move up or down do nothing.
+#[derive(Reflect, Resource, InspectorOptions, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[reflect(Resource, InspectorOptions)]
+pub enum Gravity {
+    None,
+    #[default]
+    Down,
+    DownLeft,
+    UpLeft,
+    Up,
+    UpRight,
+    DownRight,
+}
+
+impl Gravity {
+    pub const ALL: [Self; 7] = [
+        Self::None,
+        Self::Down,
+        Self::DownLeft,
+        Self::UpLeft,
+        Self::Up,
+        Self::UpRight,
+        Self::DownRight,
+    ];
+
+    /// Sixths of a turn clockwise from straight down, or `None`
+    /// without gravity.
+    pub fn turns(self) -> Option<u8> {
+        match self {
+            Gravity::None => None,
+            Gravity::Down => Some(0),
+            Gravity::DownLeft => Some(1),
+            Gravity::UpLeft => Some(2),
+            Gravity::Up => Some(3),
+            Gravity::UpRight => Some(4),
+            Gravity::DownRight => Some(5),
+        }
+    }
+
+    /// Which way is down in world space, or `None` without gravity.
+    pub fn down(self) -> Option<Vec2> {
+        self.turns()
+            .map(|turns| Vec2::from_angle((-90.0 - 60.0 * turns as f32).to_radians()))
+    }
+}
+
+/// Regions of the board that override the global [`Gravity`].
+///
+/// When regions overlap, the one added last wins.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct GravityField {
+    pub regions: Vec<(HexBounds, Gravity)>,
+}
+
+impl GravityField {
+    /// The gravity of the last region containing `hex`.
+    pub fn at(&self, hex: Hex) -> Option<Gravity> {
+        self.regions
+            .iter()
+            .rev()
+            .find(|(bounds, _gravity)| bounds.is_in_bounds(hex))
+            .map(|(_bounds, gravity)| *gravity)
+    }
+}
+
+/// Copy the gravity resources to the [`BoardState`] so rules can see
+/// them.
+pub(super) fn gravity_system(
+    gravity: Res<Gravity>,
+    field: Res<GravityField>,
+    mut states: ResMut<BoardState>,
+) {
+    states.set_gravity(*gravity, field.clone());
+}
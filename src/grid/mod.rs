@@ -1,24 +1,38 @@
 pub mod cell;
+mod conservation;
+#[cfg(test)]
+pub(crate) mod golden;
+mod gravity;
+mod pressure;
 mod state;
+mod wind;
 
 use std::{
     fs,
     ops::{Deref, DerefMut},
+    sync::atomic::{AtomicUsize, Ordering},
     time::Duration,
 };
 
 use bevy_inspector_egui::{inspector_options::ReflectInspectorOptions, InspectorOptions};
-use bevy_turborand::{DelegatedRng, GlobalRng, RngComponent};
+use bevy_turborand::{DelegatedRng, GlobalRng, RngComponent, SeededCore};
 use bytebuffer::ByteBuffer;
+pub use conservation::Conservation;
+use conservation::ConservationCheck;
+pub use gravity::{Gravity, GravityField};
 use noisy_bevy::simplex_noise_2d;
-use rayon::iter::{ParallelBridge, ParallelIterator};
+pub use pressure::Pressure;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
 pub use state::BoardState;
 use unique_type_id::UniqueTypeId as _;
+pub use wind::{WindField, WindOverlay};
 
 use crate::{
-    grid::cell::{Air, Fire, Sand, Water},
+    behavior::StateId,
+    grid::cell::{Air, Drain, Emitter, Fire, Sand, Spout, Water},
     input::Input,
-    ui::Palette,
+    rng::CellRng,
+    ui::{Palette, Tool},
     GameEvent, SimState,
 };
 use bevy::{
@@ -28,15 +42,28 @@ use bevy::{
 use hexx::*;
 use leafwing_input_manager::prelude::*;
 
-use self::cell::{CellRegistry, HexColor};
+use self::cell::{BoardSlice, CellData, CellRegistry, HexColor, UpdateMode};
 
 pub(super) struct Plugin {
     range: u32,
+    orientation: HexOrientation,
+    hex_size: f32,
 }
 
 impl Plugin {
     pub fn new(range: u32) -> Self {
-        Self { range }
+        Self {
+            range,
+            orientation: HexOrientation::Pointy,
+            hex_size: 2.0,
+        }
+    }
+
+    /// Lay the board out with flat or pointy tops and hexes of `hex_size`.
+    pub fn with_layout(mut self, orientation: HexOrientation, hex_size: f32) -> Self {
+        self.orientation = orientation;
+        self.hex_size = hex_size;
+        self
     }
 }
 
@@ -45,10 +72,21 @@ impl bevy::prelude::Plugin for Plugin {
         app.add_plugins(cell::Plugin);
 
         // Adjust the size and layout of the board.
-        let states = BoardState::new(self.range);
+        let states = BoardState::new(self.range, app.world.resource::<CellRegistry>())
+            .with_layout(self.orientation, self.hex_size);
         app.insert_resource(states);
 
         app.insert_resource(TickRate::new(Duration::from_millis(15)));
+        app.init_resource::<Conservation>();
+        app.init_resource::<ConservationCheck>();
+        app.register_type::<Gravity>();
+        app.init_resource::<Gravity>();
+        app.init_resource::<GravityField>();
+        app.register_type::<WindField>();
+        app.register_type::<WindOverlay>();
+        app.init_resource::<WindField>();
+        app.init_resource::<Pressure>();
+        app.init_resource::<WindOverlay>();
         app.add_event::<TickEvent>();
         app.add_event::<FlushEvent>();
 
@@ -77,6 +115,22 @@ impl bevy::prelude::Plugin for Plugin {
         schedule.insert_after(CellRender, CellPostUpdate);
 
         app.add_systems(CellPreUpdate, control_system);
+        app.add_systems(
+            CellPreUpdate,
+            gravity::gravity_system
+                .run_if(resource_changed::<Gravity>.or_else(resource_changed::<GravityField>)),
+        );
+        app.add_systems(
+            CellPreUpdate,
+            wind::wind_system
+                .after(control_system)
+                .run_if(on_event::<TickEvent>().or_else(resource_changed::<WindField>)),
+        );
+        app.add_systems(Update, wind::wind_overlay_system);
+        app.add_systems(
+            CellPreUpdate,
+            pressure::pressure_system.run_if(on_event::<TickEvent>()),
+        );
         app.add_systems(CellUpdate, sim_system.run_if(on_event::<TickEvent>()));
         app.add_systems(
             CellRender,
@@ -86,6 +140,12 @@ impl bevy::prelude::Plugin for Plugin {
             CellPostUpdate,
             flush_system.run_if(on_event::<TickEvent>().or_else(on_event::<FlushEvent>())),
         );
+        app.add_systems(
+            CellPostUpdate,
+            conservation_system
+                .after(flush_system)
+                .run_if(on_event::<TickEvent>()),
+        );
     }
 }
 
@@ -129,6 +189,13 @@ pub fn startup_system(
 ) {
     let mut entities = HexEntities::default();
     let texture = asset_loader.load::<Image>("hex.png");
+    let layout = states.layout();
+    // The sprite is drawn for a pointy hex of size 2
+    let scale = 0.063 * layout.hex_size.x / 2.0;
+    let rotation = match layout.orientation {
+        HexOrientation::Pointy => Quat::IDENTITY,
+        HexOrientation::Flat => Quat::from_rotation_z(std::f32::consts::FRAC_PI_6),
+    };
     for hex in states.bounds().all_coords() {
         let mut entity = commands.spawn_empty();
         entities.insert(hex, entity.id());
@@ -136,10 +203,9 @@ pub fn startup_system(
         entity.insert(HexPosition(hex));
         entity.insert(RngComponent::from(&mut rng));
         entity.insert(SpriteBundle {
-            transform: Transform::from_translation(
-                states.layout().hex_to_world_pos(hex).extend(0.0),
-            )
-            .with_scale(Vec3::new(0.063, 0.063, 1.0)),
+            transform: Transform::from_translation(layout.hex_to_world_pos(hex).extend(0.0))
+                .with_rotation(rotation)
+                .with_scale(Vec3::new(scale, scale, 1.0)),
             ..default()
         });
         entity.insert(texture.clone());
@@ -221,32 +287,105 @@ fn tick_system(
     }
 }
 
-/// System to run the simulation every frame.
-fn sim_system(states: Res<BoardState>, registry: Res<CellRegistry>, mut rng: ResMut<GlobalRng>) {
+/// How the asynchronous cells of a tick are visited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Schedule {
+    /// Spread across threads, so the order slices are applied in can
+    /// change from run to run.
+    Parallel,
+    /// One at a time in the shuffled order, so the result only
+    /// depends on the rng. Used by the golden and conservation tests.
+    #[cfg_attr(not(test), allow(dead_code))]
+    Sequential,
+}
+
+/// Tick every cell on the board once, in a random order drawn from
+/// `rng`. The changes are queued in [`BoardState::next`] and still
+/// have to be committed.
+///
+/// Synchronous cells all see the board as it was at the start of the
+/// tick, and are applied before any asynchronous cell is ticked. With
+/// a `check`, slices that would break conservation are skipped.
+///
+/// Returns how many hexes were written by the slices that were
+/// applied, counting a hex again each time it was written.
+pub(crate) fn tick_cells(
+    states: &BoardState,
+    registry: &CellRegistry,
+    check: Option<&ConservationCheck>,
+    rng: &mut impl DelegatedRng,
+    schedule: Schedule,
+) -> usize {
     let positions = rng.sample_multiple(&states.positions, states.bounds().hex_count());
+    let (synchronous, asynchronous): (Vec<Hex>, Vec<Hex>) =
+        positions.into_iter().copied().partition(|hex| {
+            registry[states.get_current(*hex).unwrap()].update == UpdateMode::Synchronous
+        });
+    // Seeds are drawn up front so every cell gets the same stream
+    // whichever schedule is used.
+    let synchronous = synchronous
+        .into_iter()
+        .map(|hex| (hex, rng.u64(..)))
+        .collect::<Vec<_>>();
+    let asynchronous = asynchronous
+        .into_iter()
+        .map(|hex| (hex, rng.u64(..)))
+        .collect::<Vec<_>>();
+
+    let tick = |(hex, seed): (Hex, u64)| {
+        let state = states.get_current(hex).unwrap();
+        let cell = registry.get(state).unwrap();
+        cell.behavior
+            .tick(hex, states, &mut CellRng::with_seed(seed))
+            .map(|slice| (hex, *state, slice))
+    };
+    let written = AtomicUsize::new(0);
+    let apply = |(hex, rule, slice): (Hex, StateId, BoardSlice)| {
+        if check.is_some_and(|check| !check.allows(rule, hex, &slice, states, registry)) {
+            return;
+        }
+        let len = slice.len();
+        if states.apply(slice) {
+            written.fetch_add(len, Ordering::Relaxed);
+        }
+    };
 
-    positions
-        .iter()
-        .map(|hex| (**hex, rng.f32()))
-        .par_bridge()
-        .filter_map(|(hex, rng)| {
-            let state = states.get_current(hex).unwrap();
-            let cell = registry.get(state).unwrap();
-            cell.behavior.tick(hex, &states, rng)
-        })
-        .for_each(|mut slice| {
-            if let Ok(next) = states.next.read() {
-                if slice.iter().any(|(hex, _id)| next.contains_key(hex)) {
-                    return;
-                }
-            }
+    // Every synchronous cell sees the board as it was before any of
+    // them were applied
+    let slices = match schedule {
+        Schedule::Parallel => synchronous
+            .into_par_iter()
+            .filter_map(tick)
+            .collect::<Vec<_>>(),
+        Schedule::Sequential => synchronous.into_iter().filter_map(tick).collect::<Vec<_>>(),
+    };
+    slices.into_iter().for_each(apply);
+
+    match schedule {
+        Schedule::Parallel => asynchronous
+            .into_par_iter()
+            .filter_map(tick)
+            .for_each(apply),
+        Schedule::Sequential => asynchronous.into_iter().filter_map(tick).for_each(apply),
+    }
+    written.into_inner()
+}
 
-            if let Ok(mut next) = states.next.write() {
-                for (hex, id) in slice.drain(0..) {
-                    next.insert(hex, id);
-                }
-            }
-        });
+/// System to run the simulation every frame.
+fn sim_system(
+    states: Res<BoardState>,
+    registry: Res<CellRegistry>,
+    conservation: Res<Conservation>,
+    mut check: ResMut<ConservationCheck>,
+    mut rng: ResMut<GlobalRng>,
+) {
+    let check = if conservation.enabled {
+        check.begin(&states, &registry);
+        Some(&*check)
+    } else {
+        None
+    };
+    tick_cells(&states, &registry, check, &mut *rng, Schedule::Parallel);
 }
 
 /// Move all the queued states into the current state.
@@ -254,6 +393,25 @@ fn flush_system(mut states: ResMut<BoardState>) {
     states.commit();
 }
 
+/// Make sure no conserved states were created or destroyed in the
+/// last tick.
+fn conservation_system(
+    states: Res<BoardState>,
+    registry: Res<CellRegistry>,
+    conservation: Res<Conservation>,
+    mut check: ResMut<ConservationCheck>,
+) {
+    if !conservation.enabled {
+        return;
+    }
+    if let Err(error) = check.finish(&states, &registry) {
+        error!("{error}");
+    }
+    for violation in check.violations() {
+        debug!("{violation}");
+    }
+}
+
 /// System to enable user control over the simulation.
 #[allow(clippy::too_many_arguments)]
 fn control_system(
@@ -263,6 +421,8 @@ fn control_system(
     mut rate: ResMut<TickRate>,
     states: Res<BoardState>,
     palette: Res<Palette>,
+    mut wind: ResMut<WindField>,
+    mut last_cursor: Local<Option<Vec2>>,
     camera: Query<(&Camera, &GlobalTransform)>,
     window: Query<&Window, With<PrimaryWindow>>,
 ) {
@@ -282,29 +442,61 @@ fn control_system(
         rate.normal();
     }
 
-    if input.pressed(&Input::Select) {
-        let (camera, camera_transform) = camera.single();
-        let window = window.single();
-        if let Some(world_position) = window
-            .cursor_position()
-            .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
-            .map(|ray| ray.origin.truncate())
-        {
-            let center = states.layout().world_pos_to_hex(world_position);
-            for hex in center
-                .range(palette.brush_size)
-                .filter(|hex| states.bounds().is_in_bounds(*hex))
-                .filter(|hex| {
-                    states
-                        .get_next(*hex)
-                        .map(|id| id != palette.selected)
-                        .unwrap_or_default()
-                })
-            {
+    if !input.pressed(&Input::Select) {
+        *last_cursor = None;
+        return;
+    }
+    let (camera, camera_transform) = camera.single();
+    let window = window.single();
+    let Some(world_position) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
+        .map(|ray| ray.origin.truncate())
+    else {
+        return;
+    };
+    let center = states.layout().world_pos_to_hex(world_position);
+    let brush = center
+        .range(palette.brush_size)
+        .filter(|hex| states.bounds().is_in_bounds(*hex));
+    match palette.tool {
+        Tool::State => {
+            for hex in brush.filter(|hex| {
+                states
+                    .get_next(*hex)
+                    .map(|id| id != palette.selected)
+                    .unwrap_or_default()
+            }) {
                 states.set_next(hex, palette.selected);
             }
             flush_event.send(FlushEvent);
         }
+        Tool::Wind => {
+            // Blow the way the cursor is dragged
+            let last = last_cursor.replace(world_position);
+            let Some(drag) = last.map(|last| world_position - last) else {
+                return;
+            };
+            if drag == Vec2::ZERO {
+                return;
+            }
+            let blow = drag.normalize() * palette.wind_strength;
+            wind.painted.extend(brush.map(|hex| (hex, blow)));
+        }
+        Tool::Spout | Tool::Drain => {
+            let id = match palette.tool {
+                Tool::Spout => Spout::id(),
+                _ => Drain::id(),
+            };
+            let emitter = Emitter {
+                material: palette.selected,
+                rate: palette.emit_rate,
+            };
+            for hex in brush {
+                states.set_next_data(hex, id, emitter);
+            }
+            flush_event.send(FlushEvent);
+        }
     }
 }
 
@@ -363,6 +555,17 @@ fn sprite_render_system(
                     },
                     ..default()
                 }),
+                HexColor::Fading { color, lifetime } => entity.insert(Sprite {
+                    color: {
+                        let age = match states.get_next_data(pos.0) {
+                            Some(CellData::Age(age)) => age,
+                            _ => 0,
+                        };
+                        let left = 1.0 - (age as f32 / lifetime as f32).min(1.0);
+                        color.with_a(color.a() * left)
+                    },
+                    ..default()
+                }),
             };
         })
     });
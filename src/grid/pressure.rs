@@ -0,0 +1,183 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+use bevy_inspector_egui::{inspector_options::ReflectInspectorOptions, InspectorOptions};
+use hexx::{EdgeDirection, Hex, HexLayout};
+
+use super::{cell::Phase, BoardState};
+
+/// When enabled, liquids are pushed up through connected channels
+/// until every surface of a body of liquid is level.
+#[derive(Reflect, Resource, Default, InspectorOptions)]
+#[reflect(Resource, InspectorOptions)]
+pub struct Pressure {
+    pub enabled: bool,
+}
+
+/// The pressure of every cell, in the same order as
+/// [`BoardState::iter`].
+///
+/// Each connected body of liquid is found with a flood fill, and the
+/// pressure of a cell is how many rows it is below the highest cell of
+/// its body. Cells that aren't liquid, or have no gravity, have none.
+pub fn measure(states: &BoardState) -> Vec<f32> {
+    let elevation = states
+        .iter()
+        .map(|(hex, id)| {
+            if states.properties(*id).phase != Phase::Liquid {
+                return None;
+            }
+            Some(elevation(states.layout(), hex, states.gravity(hex).down()?))
+        })
+        .collect::<Vec<_>>();
+    let index = |hex: Hex| hex.to_hexmod_coordinates(states.bounds().radius) as usize;
+
+    let mut pressure = vec![0.0; elevation.len()];
+    let mut seen = vec![false; elevation.len()];
+    for (start, _id) in states.iter() {
+        let Some(height) = elevation[index(start)] else {
+            continue;
+        };
+        if seen[index(start)] {
+            continue;
+        }
+        seen[index(start)] = true;
+        let mut body = vec![(start, height)];
+        let mut queue = VecDeque::from([start]);
+        while let Some(hex) = queue.pop_front() {
+            for neighbor in hex.all_neighbors() {
+                if !states.bounds().is_in_bounds(neighbor) || seen[index(neighbor)] {
+                    continue;
+                }
+                if let Some(height) = elevation[index(neighbor)] {
+                    seen[index(neighbor)] = true;
+                    body.push((neighbor, height));
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        let surface = body
+            .iter()
+            .map(|(_hex, height)| *height)
+            .fold(f32::MIN, f32::max);
+        for (hex, height) in body {
+            pressure[index(hex)] = surface - height;
+        }
+    }
+    pressure
+}
+
+/// How many rows up `hex` is when `down` points down.
+fn elevation(layout: &HexLayout, hex: Hex, down: Vec2) -> f32 {
+    let up = -down;
+    let origin = layout.hex_to_world_pos(Hex::ZERO);
+    // The smallest step up between neighbors
+    let row = EdgeDirection::ALL_DIRECTIONS
+        .into_iter()
+        .map(|edge| (layout.hex_to_world_pos(edge.into_hex()) - origin).dot(up))
+        .filter(|height| *height > 1e-3)
+        .fold(f32::MAX, f32::min);
+    (layout.hex_to_world_pos(hex) - origin).dot(up) / row
+}
+
+pub(super) fn pressure_system(pressure: Res<Pressure>, mut states: ResMut<BoardState>) {
+    if pressure.enabled {
+        let measured = measure(&states);
+        states.set_pressure(measured);
+    } else {
+        states.set_pressure(Vec::new());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_turborand::GlobalRng;
+    use unique_type_id::UniqueTypeId as _;
+
+    use super::*;
+    use crate::grid::{
+        cell::{CellRegistry, Stone, Water},
+        golden,
+    };
+
+    /// A U-tube with water filled high in the left arm and only the
+    /// bottom of the right arm.
+    fn u_tube(registry: &CellRegistry) -> BoardState {
+        let mut states = BoardState::new(10, registry);
+        for hex in states.positions.clone() {
+            let pos = states.layout().hex_to_world_pos(hex);
+            let left = (-16.0..=-7.0).contains(&pos.x);
+            let right = (7.0..=16.0).contains(&pos.x);
+            let bottom = (-16.0..=16.0).contains(&pos.x) && (-18.0..=-10.0).contains(&pos.y);
+            let arms = (left || right) && (-18.0..=18.0).contains(&pos.y);
+            let id = if !(bottom || arms) {
+                Stone::id()
+            } else if bottom || (left && pos.y < 12.0) {
+                Water::id()
+            } else {
+                continue;
+            };
+            states.set_next(hex, id);
+        }
+        states.commit();
+        states
+    }
+
+    /// The highest water in the right arm.
+    fn right_level(states: &BoardState) -> f32 {
+        states
+            .iter()
+            .filter(|(_hex, id)| **id == Water::id())
+            .map(|(hex, _id)| states.layout().hex_to_world_pos(hex))
+            .filter(|pos| pos.x >= 7.0)
+            .map(|pos| pos.y)
+            .fold(f32::MIN, f32::max)
+    }
+
+    #[test]
+    fn connected_surfaces_share_pressure() {
+        let registry = CellRegistry::builtin();
+        let states = u_tube(&registry);
+        let pressure = measure(&states);
+        let top = |x: f32| {
+            states
+                .iter()
+                .zip(&pressure)
+                .filter(|((_hex, id), _pressure)| **id == Water::id())
+                .map(|((hex, _id), pressure)| (states.layout().hex_to_world_pos(hex), *pressure))
+                .filter(|(pos, _pressure)| (pos.x - x).abs() < 4.0)
+                .max_by(|(a, _), (b, _)| a.y.total_cmp(&b.y))
+                .unwrap()
+                .1
+        };
+        assert_eq!(top(-12.0), 0.0);
+        assert!(top(12.0) > 1.0);
+    }
+
+    /// The highest water in the right arm after some ticks.
+    fn settle(enabled: bool) -> (f32, f32) {
+        let registry = CellRegistry::builtin();
+        let mut states = u_tube(&registry);
+        let mut rng = GlobalRng::with_seed(0);
+        let start = right_level(&states);
+        for _ in 0..300 {
+            if enabled {
+                let pressure = measure(&states);
+                states.set_pressure(pressure);
+            }
+            golden::tick(&mut states, &registry, &mut rng);
+        }
+        (start, right_level(&states))
+    }
+
+    #[test]
+    fn water_levels_in_a_u_tube() {
+        let (start, end) = settle(true);
+        assert!(end > start + 12.0, "Water only rose from {start} to {end}");
+        let (start, end) = settle(false);
+        assert!(
+            end <= start + 3.0,
+            "Water rose from {start} to {end} without pressure"
+        );
+    }
+}
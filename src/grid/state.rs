@@ -5,13 +5,12 @@ use bytebuffer::ByteBuffer;
 use hexx::*;
 use unique_type_id::UniqueTypeId as _;
 
-use crate::behavior::{StateId, StateQuery};
+use crate::behavior::{Frame, StateId, StateQuery};
 
-use super::{cell::BoardSlice, Air};
-
-/// Lookup Entity IDs from their position on the board.
-#[derive(Resource, Default, Deref, DerefMut)]
-pub struct EntityMap(HashMap<Hex, Entity>);
+use super::{
+    cell::{Agent, BoardSlice, CellData, CellRegistry, Emitter, Properties},
+    Air, Gravity, GravityField,
+};
 
 /// The state of the board.
 #[derive(Resource)]
@@ -26,10 +25,35 @@ pub struct BoardState {
 
     /// The delta for the next frame to be applied when [`Self::tick()`] is called.
     pub next: Arc<RwLock<HashMap<Hex, StateId>>>,
+
+    /// The [`Properties`] of every state, indexed by [`StateId`].
+    properties: Vec<Properties>,
+
+    gravity: Gravity,
+    gravity_field: GravityField,
+
+    /// The wind at every cell, or empty when it is calm everywhere.
+    wind: Vec<Vec2>,
+
+    /// The pressure at every cell, or empty when pressure is off.
+    pressure: Vec<f32>,
+
+    /// The velocity of every cell, in hexes per tick.
+    velocity: Vec<Vec2>,
+
+    /// Velocities for the next frame, for the cells in [`Self::next`]
+    /// that are still moving.
+    next_velocity: RwLock<HashMap<Hex, Vec2>>,
+
+    /// The [`CellData`] of every cell that has any.
+    data: HashMap<Hex, CellData>,
+
+    /// Data for the cells in [`Self::next`] that have any.
+    next_data: RwLock<HashMap<Hex, CellData>>,
 }
 
 impl BoardState {
-    pub fn new(size: u32) -> Self {
+    pub fn new(size: u32, registry: &CellRegistry) -> Self {
         let mut current = Vec::with_capacity(size as usize);
         let count = Hex::range_count(size);
         for _ in 0..count {
@@ -46,6 +70,15 @@ impl BoardState {
             positions: bounds.all_coords().collect(),
             current,
             next: Default::default(),
+            properties: registry.properties(),
+            gravity: default(),
+            gravity_field: default(),
+            wind: Vec::new(),
+            pressure: Vec::new(),
+            velocity: vec![Vec2::ZERO; count as usize],
+            next_velocity: default(),
+            data: default(),
+            next_data: default(),
         }
     }
 
@@ -57,6 +90,129 @@ impl BoardState {
         &self.layout
     }
 
+    pub fn orientation(&self) -> HexOrientation {
+        self.layout.orientation
+    }
+
+    /// Change how the board is laid out on screen.
+    ///
+    /// Rules are written with [`Heading`]s, so they act the same way
+    /// in either orientation.
+    ///
+    /// [`Heading`]: crate::behavior::Heading
+    pub fn with_layout(mut self, orientation: HexOrientation, hex_size: f32) -> Self {
+        self.layout = HexLayout {
+            orientation,
+            hex_size: Vec2::splat(hex_size),
+            ..default()
+        };
+        self
+    }
+
+    pub fn set_gravity(&mut self, gravity: Gravity, field: GravityField) {
+        self.gravity = gravity;
+        self.gravity_field = field;
+    }
+
+    /// The gravity at `hex`, from the [`GravityField`] or else the
+    /// global [`Gravity`].
+    pub fn gravity(&self, hex: Hex) -> Gravity {
+        self.gravity_field.at(hex).unwrap_or(self.gravity)
+    }
+
+    /// Set the wind at every cell, in the same order as [`Self::iter`].
+    pub fn set_wind(&mut self, wind: Vec<Vec2>) {
+        debug_assert!(wind.is_empty() || wind.len() == self.current.len());
+        self.wind = wind;
+    }
+
+    /// The wind at `hex`. It points in world space, and its length is
+    /// the chance for a cell to be blown each tick.
+    pub fn wind(&self, hex: Hex) -> Vec2 {
+        if self.wind.is_empty() || !self.bounds.is_in_bounds(hex) {
+            return Vec2::ZERO;
+        }
+        self.wind[Self::hex_to_index(&hex, self.bounds.radius)]
+    }
+
+    /// Set the pressure at every cell, in the same order as [`Self::iter`].
+    pub fn set_pressure(&mut self, pressure: Vec<f32>) {
+        debug_assert!(pressure.is_empty() || pressure.len() == self.current.len());
+        self.pressure = pressure;
+    }
+
+    /// How many rows `hex` is below the highest surface of the liquid
+    /// it is connected to.
+    pub fn pressure(&self, hex: Hex) -> f32 {
+        if self.pressure.is_empty() || !self.bounds.is_in_bounds(hex) {
+            return 0.0;
+        }
+        self.pressure[Self::hex_to_index(&hex, self.bounds.radius)]
+    }
+
+    /// The velocity of the cell at `hex`, in hexes per tick.
+    pub fn velocity(&self, hex: Hex) -> Vec2 {
+        if !self.bounds.is_in_bounds(hex) {
+            return Vec2::ZERO;
+        }
+        self.velocity[Self::hex_to_index(&hex, self.bounds.radius)]
+    }
+
+    /// The [`CellData`] of the cell at `hex`, if it has any.
+    pub fn data(&self, hex: Hex) -> Option<CellData> {
+        self.data.get(&hex).copied()
+    }
+
+    /// The [`Emitter`] settings of the cell at `hex`, if it has any.
+    pub fn emitter(&self, hex: Hex) -> Option<Emitter> {
+        match self.data(hex)? {
+            CellData::Emitter(emitter) => Some(emitter),
+            _ => None,
+        }
+    }
+
+    /// The [`Agent`] living in the cell at `hex`, if there is one.
+    pub fn agent(&self, hex: Hex) -> Option<Agent> {
+        match self.data(hex)? {
+            CellData::Agent(agent) => Some(agent),
+            _ => None,
+        }
+    }
+
+    /// How many ticks the cell at `hex` has lived, if it keeps track.
+    pub fn age(&self, hex: Hex) -> Option<u16> {
+        match self.data(hex)? {
+            CellData::Age(age) => Some(age),
+            _ => None,
+        }
+    }
+
+    /// The [`CellData`] the cell at `hex` will have after the next
+    /// commit.
+    pub fn get_next_data(&self, hex: Hex) -> Option<CellData> {
+        if self.is_set(hex) {
+            self.next_data.read().ok()?.get(&hex).copied()
+        } else {
+            self.data(hex)
+        }
+    }
+
+    /// Set the future state of a cell along with its [`CellData`].
+    pub fn set_next_data(&self, hex: Hex, id: StateId, data: impl Into<CellData>) {
+        if let Ok(mut next) = self.next_data.write() {
+            next.insert(hex, data.into());
+        }
+        self.set_next(hex, id);
+    }
+
+    /// How headings are turned into edges for the cell at `hex`.
+    pub fn frame(&self, hex: Hex) -> Frame {
+        Frame {
+            orientation: self.orientation(),
+            gravity: self.gravity(hex),
+        }
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = (Hex, &StateId)> {
         self.current
             .iter()
@@ -87,6 +243,11 @@ impl BoardState {
         hex.to_hexmod_coordinates(range) as usize
     }
 
+    /// Get the physical [`Properties`] of a state.
+    pub fn properties(&self, id: StateId) -> &Properties {
+        &self.properties[id.0 as usize]
+    }
+
     /// Get the future [`StateId`] of a cell.
     pub fn get_next(&self, hex: impl Into<Hex>) -> Option<StateId> {
         let hex = hex.into();
@@ -98,22 +259,29 @@ impl BoardState {
     }
 
     /// Return `true` if a `hex` has one of `state`.
-    pub fn is_state<const S: usize>(&self, hex: Hex, query: &StateQuery<S>) -> bool {
-        match query {
-            StateQuery::Any(states) => self
-                .get_next(hex)
-                .map(|id| states.iter().any(|other_id| &id == other_id))
-                .unwrap_or(false),
-            StateQuery::Except(states) => self
-                .get_next(hex)
-                .map(|id| states.iter().all(|other_id| &id != other_id))
-                .unwrap_or(false),
-        }
+    pub fn is_state(&self, hex: Hex, query: &StateQuery) -> bool {
+        self.find_state(hex, query).is_some()
+    }
+
+    /// Get the future [`StateId`] of a cell if it matches `query`.
+    pub fn find_state(&self, hex: Hex, query: &StateQuery) -> Option<StateId> {
+        self.get_next(hex).filter(|id| self.matches(*id, query))
+    }
+
+    /// Return `true` if `hex` had one of `state` at the start of the
+    /// tick, ignoring any changes made since.
+    pub fn was_state(&self, hex: Hex, query: &StateQuery) -> bool {
+        self.get_current(hex)
+            .is_some_and(|id| self.matches(*id, query))
     }
 
-    pub fn find_state<const S: usize>(&self, hex: Hex, query: &StateQuery<S>) -> Option<StateId> {
-        self.get_next(hex)
-            .and_then(|id| query.iter().find(|other_id| &id == other_id))
+    /// Return `true` if `id` is one of the states in `query`.
+    pub fn matches(&self, id: StateId, query: &StateQuery) -> bool {
+        match query {
+            StateQuery::Any(states) => states.contains(id),
+            StateQuery::Except(states) => !states.contains(id),
+            StateQuery::Tagged(tags) => self.properties(id).tags.contains(*tags),
+        }
     }
 
     /// Set the future state of a cell.
@@ -135,28 +303,56 @@ impl BoardState {
         hexs.into_iter().any(|hex| self.is_set(hex))
     }
 
-    pub fn apply(&mut self, mut slice: BoardSlice) {
-        if let Ok(next) = self.next.read() {
-            if slice.iter().any(|(hex, _id)| next.contains_key(hex)) {
-                return;
-            }
+    /// Queue all of `slice` into [`Self::next`], unless any of its
+    /// hexes have already been claimed this tick.
+    ///
+    /// The check and the write happen under the same lock so two
+    /// slices applied at the same time can never both claim a hex.
+    /// Returns `true` when the slice was queued.
+    pub fn apply(&self, mut slice: BoardSlice) -> bool {
+        let Ok(mut next) = self.next.write() else {
+            return false;
+        };
+        if slice.iter().any(|(hex, _id)| next.contains_key(hex)) {
+            return false;
         }
-
-        if let Ok(mut next) = self.next.write() {
-            for (hex, id) in slice.drain(0..) {
-                next.insert(hex, id);
-            }
+        if !slice.velocities.is_empty() {
+            let Ok(mut velocity) = self.next_velocity.write() else {
+                return false;
+            };
+            velocity.extend(slice.velocities.drain(0..));
+        }
+        if !slice.data.is_empty() {
+            let Ok(mut data) = self.next_data.write() else {
+                return false;
+            };
+            data.extend(slice.data.drain(0..));
         }
+        next.extend(slice.drain(0..));
+        true
     }
 
     /// Apply all changes in [`Self::next`] to [`Self::current`].
-    pub(super) fn commit(&mut self) {
+    pub(crate) fn commit(&mut self) {
+        let Ok(mut velocity) = self.next_velocity.write() else {
+            return;
+        };
+        let Ok(mut data) = self.next_data.write() else {
+            return;
+        };
         if let Ok(mut next) = self.next.write() {
             for (hex, id) in next.drain() {
                 let i = Self::hex_to_index(&hex, self.bounds.radius);
                 self.current[i] = id;
+                self.velocity[i] = velocity.remove(&hex).unwrap_or(Vec2::ZERO);
+                match data.remove(&hex) {
+                    Some(data) => self.data.insert(hex, data),
+                    None => self.data.remove(&hex),
+                };
             }
         }
+        velocity.clear();
+        data.clear();
     }
 
     pub fn clear(&mut self) {
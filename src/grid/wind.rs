@@ -0,0 +1,118 @@
+use std::f32::consts::TAU;
+
+use bevy::{prelude::*, utils::HashMap};
+use bevy_inspector_egui::{inspector_options::ReflectInspectorOptions, InspectorOptions};
+use hexx::Hex;
+use noisy_bevy::simplex_noise_2d;
+
+use super::BoardState;
+
+/// The shape of the wind over the whole board.
+#[derive(Reflect, Debug, Default, Clone, Copy, PartialEq)]
+pub enum WindPattern {
+    #[default]
+    Calm,
+    /// The same wind everywhere.
+    Uniform(Vec2),
+    /// Wind circling counterclockwise around `center`.
+    Vortex { center: Vec2, strength: f32 },
+    /// Wind that turns smoothly across the board and over time.
+    Noise {
+        scale: f32,
+        speed: f32,
+        strength: f32,
+    },
+}
+
+impl WindPattern {
+    /// The wind at `world_pos` after `seconds`.
+    pub fn sample(&self, world_pos: Vec2, seconds: f32) -> Vec2 {
+        match *self {
+            WindPattern::Calm => Vec2::ZERO,
+            WindPattern::Uniform(wind) => wind,
+            WindPattern::Vortex { center, strength } => {
+                (world_pos - center).perp().normalize_or_zero() * strength
+            }
+            WindPattern::Noise {
+                scale,
+                speed,
+                strength,
+            } => {
+                let turn = simplex_noise_2d(world_pos * scale + Vec2::splat(seconds * speed));
+                Vec2::from_angle(turn * TAU) * strength
+            }
+        }
+    }
+}
+
+/// The wind blowing gases, powders and embers around the board.
+///
+/// Painted wind replaces the [`WindPattern`] in its cells.
+#[derive(Reflect, Resource, InspectorOptions, Debug, Default, Clone)]
+#[reflect(Resource, InspectorOptions)]
+pub struct WindField {
+    pub pattern: WindPattern,
+    #[reflect(ignore)]
+    pub painted: HashMap<Hex, Vec2>,
+}
+
+impl WindField {
+    pub fn is_calm(&self) -> bool {
+        self.pattern == WindPattern::Calm && self.painted.is_empty()
+    }
+
+    /// The wind at `hex`, which is at `world_pos`, after `seconds`.
+    pub fn sample(&self, hex: Hex, world_pos: Vec2, seconds: f32) -> Vec2 {
+        match self.painted.get(&hex) {
+            Some(wind) => *wind,
+            None => self.pattern.sample(world_pos, seconds),
+        }
+    }
+}
+
+/// Draw an arrow for the wind over part of the board.
+#[derive(Reflect, Resource, InspectorOptions, Default)]
+#[reflect(Resource, InspectorOptions)]
+pub struct WindOverlay {
+    pub enabled: bool,
+}
+
+/// Only every few hexes get an arrow, to keep the overlay readable.
+const OVERLAY_SPACING: i32 = 4;
+
+/// Sample the [`WindField`] into the [`BoardState`] so rules can see
+/// it.
+pub(super) fn wind_system(field: Res<WindField>, time: Res<Time>, mut states: ResMut<BoardState>) {
+    if field.is_calm() {
+        states.set_wind(Vec::new());
+        return;
+    }
+    let seconds = time.elapsed_seconds();
+    let wind = states
+        .iter()
+        .map(|(hex, _id)| field.sample(hex, states.layout().hex_to_world_pos(hex), seconds))
+        .collect();
+    states.set_wind(wind);
+}
+
+pub(super) fn wind_overlay_system(
+    overlay: Res<WindOverlay>,
+    states: Res<BoardState>,
+    mut gizmos: Gizmos,
+) {
+    if !overlay.enabled {
+        return;
+    }
+    let length = states.layout().hex_size.x * OVERLAY_SPACING as f32;
+    for hex in &states.positions {
+        if hex.x % OVERLAY_SPACING != 0 || hex.y % OVERLAY_SPACING != 0 {
+            continue;
+        }
+        let wind = states.wind(*hex);
+        if wind == Vec2::ZERO {
+            continue;
+        }
+        let start = states.layout().hex_to_world_pos(*hex);
+        gizmos.arrow_2d(start, start + wind * length, Color::WHITE);
+    }
+}
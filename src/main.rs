@@ -55,7 +55,12 @@ fn main() {
     app.add_plugins(rng::Plugin);
     app.add_plugins(camera::Plugin);
     app.add_plugins(input::Plugin);
-    app.add_plugins(grid::Plugin::new(100));
+    let orientation = if std::env::args().any(|arg| arg == "--flat") {
+        hexx::HexOrientation::Flat
+    } else {
+        hexx::HexOrientation::Pointy
+    };
+    app.add_plugins(grid::Plugin::new(100).with_layout(orientation, 2.0));
     app.add_plugins(ui::Plugin {
         initial_selected: Air::id(),
         initial_brush_size: 1,
@@ -1,6 +1,13 @@
 use bevy::prelude::*;
 use bevy_turborand::prelude::*;
 
+/// The random stream a single cell draws from while it ticks.
+///
+/// Each cell gets its own, seeded from the [`GlobalRng`], so every
+/// decision a [`Step`](crate::behavior::Step) makes can draw a fresh
+/// value and the result still only depends on the global seed.
+pub type CellRng = bevy_turborand::rng::Rng;
+
 pub struct Plugin;
 
 impl bevy::prelude::Plugin for Plugin {
@@ -19,7 +19,7 @@ use crate::{
     grid::{
         self,
         cell::{Air, CellRegistry},
-        BoardState, FlushEvent, TickRate,
+        BoardState, Conservation, FlushEvent, Gravity, Pressure, TickRate, WindField, WindOverlay,
     },
     input::Input,
     GameEvent, SimState,
@@ -42,6 +42,7 @@ impl bevy::prelude::Plugin for Plugin {
         app.insert_resource(Palette {
             selected: self.initial_selected,
             brush_size: self.initial_brush_size,
+            ..default()
         });
 
         app.insert_resource(SaveLocation(Cow::Owned(self.initial_save_location.clone())));
@@ -108,6 +109,34 @@ fn update_system(world: &mut World) {
             }
         });
 
+        ui.add_space(16.);
+        ui.push_id(Id::from("conservation"), |ui| {
+            ui.heading("Conservation");
+            bevy_inspector::ui_for_resource::<Conservation>(world, ui);
+        });
+
+        ui.add_space(16.);
+        ui.push_id(Id::from("pressure"), |ui| {
+            ui.heading("Pressure");
+            bevy_inspector::ui_for_resource::<Pressure>(world, ui);
+        });
+
+        ui.add_space(16.);
+        ui.push_id(Id::from("gravity"), |ui| {
+            ui.heading("Gravity");
+            bevy_inspector::ui_for_resource::<Gravity>(world, ui);
+        });
+
+        ui.add_space(16.);
+        ui.push_id(Id::from("wind"), |ui| {
+            ui.heading("Wind");
+            bevy_inspector::ui_for_resource::<WindField>(world, ui);
+            bevy_inspector::ui_for_resource::<WindOverlay>(world, ui);
+            if ui.button("Clear Painted Wind").clicked() {
+                world.resource_mut::<WindField>().painted.clear();
+            }
+        });
+
         ui.add_space(16.);
         ui.push_id(Id::from("control"), |ui| {
             ui.horizontal_top(|ui| {
@@ -140,6 +169,20 @@ fn update_system(world: &mut World) {
             let registry = world.resource::<CellRegistry>().names().collect::<Vec<_>>();
             let mut palette = world.resource_mut::<Palette>();
             ui.add(egui::Slider::new(&mut palette.brush_size, 0..=100));
+            ui.radio_value(&mut palette.tool, Tool::State, "States");
+            ui.radio_value(&mut palette.tool, Tool::Wind, "Wind");
+            ui.radio_value(&mut palette.tool, Tool::Spout, "Spout");
+            ui.radio_value(&mut palette.tool, Tool::Drain, "Drain");
+            match palette.tool {
+                Tool::Wind => {
+                    ui.add(egui::Slider::new(&mut palette.wind_strength, 0.0..=1.0));
+                }
+                Tool::Spout | Tool::Drain => {
+                    ui.add(egui::Slider::new(&mut palette.emit_rate, 0.0..=1.0));
+                }
+                Tool::State => {}
+            }
+            ui.separator();
             let mut cells = registry.into_iter().collect::<Vec<_>>();
             cells.sort_by(|(_id_a, name_a), (_id_b, name_b)| name_a.cmp(name_b));
             for (id, name) in cells {
@@ -181,10 +224,29 @@ fn tooltip_system(
     }
 }
 
+/// What the brush does to the board.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    /// Paint the selected state.
+    #[default]
+    State,
+    /// Paint wind in the direction the brush is dragged.
+    Wind,
+    /// Paint spouts that make the selected state.
+    Spout,
+    /// Paint drains that remove the selected state.
+    Drain,
+}
+
 #[derive(Resource)]
 pub struct Palette {
     pub selected: StateId,
     pub brush_size: u32,
+    pub tool: Tool,
+    /// The strength of painted wind, from 0 to 1.
+    pub wind_strength: f32,
+    /// The chance each tick that painted spouts and drains act.
+    pub emit_rate: f32,
 }
 
 impl Deref for Palette {
@@ -206,6 +268,9 @@ impl Default for Palette {
         Self {
             selected: Air::id(),
             brush_size: 1,
+            tool: Tool::State,
+            wind_strength: 0.5,
+            emit_rate: 0.5,
         }
     }
 }